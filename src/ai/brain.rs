@@ -0,0 +1,381 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use rand::{seq::SliceRandom, Rng};
+
+use super::{item_power, landings, Difficulty, Evaluator, Search};
+use crate::{
+    board::TileId,
+    dice::Roll,
+    rng::GameRng,
+    rules::{
+        Command, Fighter, Goods, MatchState, Phase, SpellDef, SpellEffect, SpellTiming, Victory,
+        MOVEMENT_DICE,
+    },
+};
+
+// gold a point of value is worth when deciding if something is a bargain
+const GOLD_PER_POINT: i32 = 6;
+
+// a computer player for one seat, it looks at the match and answers with the next command,
+// where the token ends up decides the battles it gets into and the odds if it fights them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Brain {
+    seat: usize,
+    difficulty: Difficulty,
+    search: Option<Search>,
+    rng: GameRng,
+}
+
+impl Brain {
+    // every seat thinks with its own generator taken from the match seed, so the same
+    // match always gets the same decisions without touching the rolls of the match
+    pub fn new(seat: usize, difficulty: Difficulty, seed: u64) -> Self {
+        let salt = (seat as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        Self {
+            seat,
+            difficulty,
+            search: difficulty.search(),
+            rng: GameRng::from_seed(seed ^ salt),
+        }
+    }
+
+    // without a search the brain only follows the heuristics of its difficulty
+    pub fn with_search(self, search: Option<Search>) -> Self {
+        Self { search, ..self }
+    }
+
+    pub fn seat(&self) -> usize {
+        self.seat
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    // None when it is not this seat's turn or the match is over
+    pub fn decide(&mut self, state: &MatchState) -> Option<Command> {
+        if state.current != self.seat || state.players[self.seat].out {
+            return None;
+        }
+        let prepared = match &state.phase {
+            Phase::Roll => self.prepare(state),
+            _ => None,
+        };
+        if prepared.is_some() {
+            return prepared;
+        }
+        let hunch = self.hunch(state)?;
+        match self.search {
+            Some(search) => search.best(state, self.seat, hunch, &mut self.rng),
+            None => Some(hunch),
+        }
+    }
+
+    // what the heuristics of the difficulty would do, the search only overrules it when
+    // another choice plays out better
+    fn hunch(&mut self, state: &MatchState) -> Option<Command> {
+        let command = match &state.phase {
+            Phase::Roll => self.cast_before_roll(state).unwrap_or(Command::Roll),
+            Phase::Rolled { roll } => self.cast_after_roll(state, roll).unwrap_or(Command::Move),
+            Phase::Choose { remaining, options } => {
+                Command::Choose(self.choose_path(state, *remaining, options))
+            }
+            Phase::Battle { defender, .. } => self.battle(state, *defender),
+            Phase::Shop { tile } => self.shop(state, *tile),
+            Phase::Over { .. } => return None,
+        };
+        Some(command)
+    }
+
+    // runs away when losing is likelier than winning, the easy brain always fights
+    fn battle(&self, state: &MatchState, defender: Fighter) -> Command {
+        if self.difficulty == Difficulty::Easy {
+            return Command::Fight;
+        }
+        let (won, lost) = Evaluator::new(state, self.seat, self.difficulty).battle_odds(defender);
+        if lost > won {
+            Command::Flee
+        } else {
+            Command::Fight
+        }
+    }
+
+    fn blunders(&mut self) -> bool {
+        self.rng.gen_range(0..100) < self.difficulty.blunder()
+    }
+
+    // heals when hurt and puts on anything better than what is worn
+    fn prepare(&mut self, state: &MatchState) -> Option<Command> {
+        let player = &state.players[self.seat];
+        let items = &state.data.items;
+        let hurt = match self.difficulty {
+            Difficulty::Easy => player.health <= player.stats.max_health / 4,
+            Difficulty::Normal | Difficulty::Hard => player.health <= player.stats.max_health / 2,
+        };
+        if hurt {
+            let missing = player.stats.max_health.saturating_sub(player.health);
+            // the smallest cure that heals it all, or else the biggest one
+            let cure = player
+                .inventory
+                .iter()
+                .enumerate()
+                .filter_map(|(index, item)| Some((index, items.item(item)?.heal)))
+                .filter(|(_, heal)| *heal > 0)
+                .min_by_key(|(index, heal)| (*heal < missing, heal.abs_diff(missing), *index));
+            if let Some((index, _)) = cure {
+                return Some(Command::UseItem(index));
+            }
+        }
+
+        player
+            .inventory
+            .iter()
+            .enumerate()
+            .find(|(_, id)| {
+                let Some(item) = items.item(id) else {
+                    return false;
+                };
+                let Some(slot) = item.slot else {
+                    return false;
+                };
+                match player
+                    .equipment
+                    .get(&slot)
+                    .and_then(|worn| items.item(worn))
+                {
+                    None => true,
+                    Some(_) if self.difficulty == Difficulty::Easy => false,
+                    Some(worn) => item_power(item) > item_power(worn),
+                }
+            })
+            .map(|(index, _)| Command::Equip(index))
+    }
+
+    // in a key runes match the last of a key rune is never spent
+    fn can_spare(&self, state: &MatchState, spell: &SpellDef) -> bool {
+        if state.rules.victory != Victory::KeyRunes {
+            return true;
+        }
+        let player = &state.players[self.seat];
+        spell.cost.iter().all(|(rune, count)| {
+            let key = state.data.magic.rune(rune).is_some_and(|rune| rune.key);
+            !key || player.runes.get(rune).copied().unwrap_or_default() > *count
+        })
+    }
+
+    // the strongest opponent still playing, if it is ahead of us
+    fn leader(&self, state: &MatchState) -> Option<usize> {
+        let own = state.score(&state.players[self.seat]);
+        let leader = state
+            .players
+            .iter()
+            .filter(|other| other.seat != self.seat && !other.out)
+            .max_by_key(|other| (state.score(other), other.gold))?;
+        let score = state.score(leader);
+        let ahead = match self.difficulty {
+            Difficulty::Hard => score >= own,
+            Difficulty::Easy | Difficulty::Normal => score > own,
+        };
+        ahead.then_some(leader.seat)
+    }
+
+    fn cast_before_roll(&mut self, state: &MatchState) -> Option<Command> {
+        let margin = self.difficulty.cast_margin()?;
+        let seat = self.seat;
+        let tile = state.players[seat].tile;
+        let spells = state
+            .castable_spells()
+            .into_iter()
+            .filter(|spell| self.can_spare(state, spell))
+            .cloned()
+            .collect::<Vec<_>>();
+        let leader = self.leader(state);
+        let mut evaluator = Evaluator::new(state, seat, self.difficulty);
+        let mut staying = None;
+        for spell in spells {
+            match spell.effect {
+                SpellEffect::Hinder(_) => {
+                    let Some(target) = leader else {
+                        continue;
+                    };
+                    if state.players[target].hindered == 0 {
+                        return Some(Command::Cast {
+                            spell: spell.id,
+                            target: Some(target),
+                        });
+                    }
+                }
+                SpellEffect::Teleport(kind) => {
+                    // only the ones thinking ahead know where a roll from there would go
+                    if !self.difficulty.looks_ahead() || state.board.tile(tile).kind == kind {
+                        continue;
+                    }
+                    let distances = state.board.distances_from(tile);
+                    let Some(destination) = state
+                        .board
+                        .tiles_of(kind)
+                        .filter(|other| other.id != tile)
+                        .filter_map(|other| distances[other.id.0].map(|far| (far, other.id)))
+                        .min()
+                        .map(|(_, id)| id)
+                    else {
+                        continue;
+                    };
+                    let staying = *staying.get_or_insert_with(|| evaluator.roll_value(tile, 0));
+                    if evaluator.roll_value(destination, 0) > staying + margin {
+                        return Some(Command::Cast {
+                            spell: spell.id,
+                            target: None,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // spends runes on the roll only when it makes the landing clearly better
+    fn cast_after_roll(&mut self, state: &MatchState, roll: &Roll) -> Option<Command> {
+        let margin = self.difficulty.cast_margin()?;
+        let seat = self.seat;
+        let tile = state.players[seat].tile;
+        let spells = state
+            .castable_spells()
+            .into_iter()
+            .filter(|spell| spell.effect.timing() == SpellTiming::AfterRoll)
+            .filter(|spell| self.can_spare(state, spell))
+            .cloned()
+            .collect::<Vec<_>>();
+        if spells.is_empty() {
+            return None;
+        }
+        let mut evaluator = Evaluator::new(state, seat, self.difficulty);
+        let current = evaluator.steps_value(tile, roll.total());
+        let mut best: Option<(i32, String)> = None;
+        for spell in spells {
+            let value = match spell.effect {
+                SpellEffect::AddPips(pips) => evaluator.steps_value(tile, roll.total() + pips),
+                SpellEffect::ForceFace(face) => {
+                    let mut forced = roll.clone();
+                    if let Some(lowest) = forced.faces.iter_mut().min() {
+                        *lowest = face.min(MOVEMENT_DICE.sides);
+                    }
+                    evaluator.steps_value(tile, forced.total())
+                }
+                SpellEffect::Reroll => evaluator.roll_value(tile, roll.bonus),
+                SpellEffect::Teleport(_) | SpellEffect::Hinder(_) => continue,
+            };
+            if value > current + margin && best.as_ref().is_none_or(|(best, _)| value > *best) {
+                best = Some((value, spell.id));
+            }
+        }
+        best.map(|(_, spell)| Command::Cast {
+            spell,
+            target: None,
+        })
+    }
+
+    fn choose_path(&mut self, state: &MatchState, remaining: u32, options: &[TileId]) -> TileId {
+        if self.blunders() {
+            if let Some(option) = options.choose(&mut self.rng) {
+                return *option;
+            }
+        }
+        let tile = state.players[self.seat].tile;
+        let mut evaluator = Evaluator::new(state, self.seat, self.difficulty);
+        options
+            .iter()
+            .map(|option| {
+                let value = landings(&state.board, tile, remaining, &[*option])
+                    .into_iter()
+                    .map(|landing| evaluator.landing_value(landing))
+                    .max()
+                    .unwrap_or_default();
+                (value, *option)
+            })
+            .max_by_key(|(value, option)| (*value, std::cmp::Reverse(*option)))
+            .map_or(tile, |(_, option)| option)
+    }
+
+    // sells what is of no use, then buys the best bargain until there are none left
+    fn shop(&mut self, state: &MatchState, tile: TileId) -> Command {
+        let player = &state.players[self.seat];
+        let items = &state.data.items;
+        let Some((shop, def)) = state.shop_at(tile) else {
+            return Command::LeaveShop;
+        };
+
+        if self.difficulty == Difficulty::Hard {
+            let junk = player.inventory.iter().position(|id| {
+                let Some(item) = items.item(id) else {
+                    return false;
+                };
+                let worn = item
+                    .slot
+                    .and_then(|slot| player.equipment.get(&slot))
+                    .and_then(|worn| items.item(worn));
+                worn.is_some_and(|worn| item_power(item) <= item_power(worn))
+            });
+            if let Some(index) = junk {
+                return Command::SellItem(index);
+            }
+        }
+
+        let room = player.inventory.len() < items.inventory_slots;
+        let affordable = def
+            .stock
+            .iter()
+            .enumerate()
+            .filter(|(index, entry)| {
+                shop.quantities[*index] > 0 && (room || !matches!(entry.goods, Goods::Item(_)))
+            })
+            .filter_map(|(index, entry)| {
+                let price = state.shop_price(tile, index)?;
+                (price <= player.gold).then_some((index, &entry.goods, price))
+            })
+            .collect::<Vec<_>>();
+
+        if self.blunders() {
+            return match affordable.choose(&mut self.rng) {
+                Some((index, _, _)) if self.rng.gen_bool(0.5) => Command::Buy(*index),
+                _ => Command::LeaveShop,
+            };
+        }
+        // gold is the score of those matches, anything bought sells back for less
+        if matches!(state.rules.victory, Victory::Gold | Victory::TurnLimit) {
+            return Command::LeaveShop;
+        }
+        let evaluator = Evaluator::new(state, self.seat, self.difficulty);
+        affordable
+            .into_iter()
+            .map(|(index, goods, price)| {
+                let value = evaluator.goods_value(goods);
+                (value * GOLD_PER_POINT - price as i32, value, index)
+            })
+            .filter(|(bargain, value, _)| *value > 0 && *bargain >= 0)
+            .max_by_key(|(bargain, _, index)| (*bargain, std::cmp::Reverse(*index)))
+            .map_or(Command::LeaveShop, |(_, _, index)| Command::Buy(index))
+    }
+}
@@ -0,0 +1,83 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::Search;
+
+// how well a computer player plays, every level uses the same rules but weighs them
+// with more or less care
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default, Deserialize, Serialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    // percentage of the choices taken at random instead of the best one
+    pub fn blunder(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 50,
+            Difficulty::Normal => 10,
+            Difficulty::Hard => 0,
+        }
+    }
+
+    // how much better a spell has to make the turn before runes are spent on it
+    pub fn cast_margin(&self) -> Option<i32> {
+        match self {
+            Difficulty::Easy => None,
+            Difficulty::Normal => Some(3),
+            Difficulty::Hard => Some(4),
+        }
+    }
+
+    // a landing is also judged by where the next roll could go from there
+    pub fn looks_ahead(&self) -> bool {
+        *self == Difficulty::Hard
+    }
+
+    // the hard brain plays its choices out instead of trusting its heuristics
+    pub fn search(&self) -> Option<Search> {
+        match self {
+            Difficulty::Easy | Difficulty::Normal => None,
+            // a turn can't wait for every rollout on a slow machine
+            Difficulty::Hard => Some(Search {
+                budget: Some(Duration::from_millis(500)),
+                ..Search::default()
+            }),
+        }
+    }
+}
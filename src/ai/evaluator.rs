@@ -0,0 +1,295 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::collections::HashMap;
+
+use super::Difficulty;
+use crate::{
+    board::{Board, TileId, TileKind},
+    dice::DicePool,
+    rules::{
+        odds, walk, Fighter, Goods, ItemDef, MatchState, Upgrade, Victory, Walk, MOVEMENT_DICE,
+    },
+};
+
+// every tile a walk can end on, following each branch of the forks on the way
+pub fn landings(board: &Board, from: TileId, steps: u32, choices: &[TileId]) -> Vec<TileId> {
+    match walk(board, from, steps, choices) {
+        Ok(Walk::Arrived { path }) => vec![path.last().copied().unwrap_or(from)],
+        Ok(Walk::Fork { options, .. }) => options
+            .iter()
+            .flat_map(|option| {
+                let choices = choices.iter().chain([option]).copied().collect::<Vec<_>>();
+                landings(board, from, steps, &choices)
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+// the chance of every tile the pool can take a player to, when the path forks any tile
+// along a branch can be reached by choosing it
+pub fn reach_chances(board: &Board, from: TileId, pool: DicePool) -> HashMap<TileId, f64> {
+    let distribution = pool.distribution();
+    let mut chances = HashMap::new();
+    for (total, _) in distribution.totals() {
+        let mut tiles = landings(board, from, total.max(1) as u32, &[]);
+        tiles.sort();
+        tiles.dedup();
+        for tile in tiles {
+            *chances.entry(tile).or_default() += distribution.chance(total);
+        }
+    }
+    chances
+}
+
+// a rough worth of an item in battle, a die counts as its average roll
+pub fn item_power(item: &ItemDef) -> i32 {
+    item.attack + item.defense + (item.attack_dice as i32 + item.defense_dice as i32) * 7 / 2
+}
+
+// judges tiles and goods for one seat, tile values are remembered since the same tiles
+// come up again and again while weighing a single decision
+pub struct Evaluator<'a> {
+    state: &'a MatchState,
+    seat: usize,
+    difficulty: Difficulty,
+    values: HashMap<TileId, i32>,
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn new(state: &'a MatchState, seat: usize, difficulty: Difficulty) -> Self {
+        Self {
+            state,
+            seat,
+            difficulty,
+            values: HashMap::new(),
+        }
+    }
+
+    // who would fight the player landing there, the same way the match decides it
+    pub fn defender_at(&self, tile: TileId) -> Option<Fighter> {
+        if tile == self.state.board.start() {
+            return None;
+        }
+        self.state.defender_at(self.seat, tile)
+    }
+
+    // chances out of 100 of winning and of losing a battle started against the defender,
+    // worked out from the dice the same way the battle scene shows them
+    pub fn battle_odds(&self, defender: Fighter) -> (i32, i32) {
+        let attacker = self.state.combatant(Fighter::Player(self.seat));
+        let defender = self.state.combatant(defender);
+        let odds = odds(&attacker, &defender);
+        (
+            (odds.attacker_wins * 100.).round() as i32,
+            (odds.defender_wins * 100.).round() as i32,
+        )
+    }
+
+    // what ending the move on a tile is worth right now, fights weigh their odds
+    pub fn tile_value(&mut self, tile: TileId) -> i32 {
+        if let Some(value) = self.values.get(&tile) {
+            return *value;
+        }
+        let value = match self.defender_at(tile) {
+            Some(defender) => self.fight_value(defender),
+            None => self.kind_value(tile),
+        };
+        self.values.insert(tile, value);
+        value
+    }
+
+    fn kind_value(&self, tile: TileId) -> i32 {
+        let state = self.state;
+        let player = &state.players[self.seat];
+        match state.board.tile(tile).kind {
+            TileKind::Rune if state.rules.victory == Victory::KeyRunes => 6,
+            TileKind::Rune => 3,
+            // a town is worth the best it sells that we can pay, unless hoarding wins
+            TileKind::Town
+                if !matches!(state.rules.victory, Victory::Gold | Victory::TurnLimit) =>
+            {
+                self.shop_value(tile)
+            }
+            TileKind::Shrine
+                if state
+                    .data
+                    .magic
+                    .spells
+                    .iter()
+                    .any(|spell| !player.knows(&spell.id)) =>
+            {
+                3
+            }
+            TileKind::Event => 1,
+            _ => 0,
+        }
+    }
+
+    fn shop_value(&self, tile: TileId) -> i32 {
+        let state = self.state;
+        let Some((shop, def)) = state.shop_at(tile) else {
+            return 0;
+        };
+        let gold = state.players[self.seat].gold;
+        def.stock
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| shop.quantities[*index] > 0)
+            .filter(|(index, _)| {
+                state
+                    .shop_price(tile, *index)
+                    .is_some_and(|price| price <= gold)
+            })
+            .map(|(_, entry)| self.goods_value(&entry.goods))
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn fight_value(&self, defender: Fighter) -> i32 {
+        let state = self.state;
+        let victory = state.rules.victory;
+        let reward = match defender {
+            Fighter::Monster(id) => state
+                .monster(id)
+                .and_then(|monster| state.data.monsters.monster(&monster.kind))
+                .map_or(0, |def| {
+                    let gold = def.gold as i32 * if victory == Victory::Gold { 2 } else { 1 };
+                    gold + if def.loot.is_empty() { 0 } else { 2 }
+                }),
+            Fighter::Player(_) if victory == Victory::LastStanding => 10,
+            Fighter::Player(_) => 3,
+        };
+        let loss = if victory == Victory::LastStanding {
+            20
+        } else {
+            8
+        };
+        let (won, lost) = self.battle_odds(defender);
+        (won * reward - lost * loss) / 100
+    }
+
+    // a landing, and for those looking ahead also where the next roll could go from it
+    pub fn landing_value(&mut self, tile: TileId) -> i32 {
+        let value = self.tile_value(tile);
+        if self.difficulty.looks_ahead() {
+            value + self.roll_value(tile, 0) / 2
+        } else {
+            value
+        }
+    }
+
+    // the best landing moving a number of steps, the forks on the way will be chosen well
+    pub fn steps_value(&mut self, from: TileId, total: i32) -> i32 {
+        let steps = total.max(1) as u32;
+        landings(&self.state.board, from, steps, &[])
+            .into_iter()
+            .map(|tile| self.landing_value(tile))
+            .max()
+            .unwrap_or_default()
+    }
+
+    // the average best tile over every roll the movement dice can make
+    pub fn roll_value(&mut self, from: TileId, bonus: i32) -> i32 {
+        let distribution = MOVEMENT_DICE.with_bonus(bonus).distribution();
+        let mut by_steps = HashMap::new();
+        let mut sum = 0.;
+        for (total, chance) in distribution.totals() {
+            let steps = total.max(1) as u32;
+            let best = *by_steps.entry(steps).or_insert_with(|| {
+                landings(&self.state.board, from, steps, &[])
+                    .into_iter()
+                    .map(|tile| self.tile_value(tile))
+                    .max()
+                    .unwrap_or_default()
+            });
+            sum += best as f64 * chance;
+        }
+        sum as i32
+    }
+
+    // what buying something adds, nothing for what can't be used or is already better
+    pub fn goods_value(&self, goods: &Goods) -> i32 {
+        let state = self.state;
+        let player = &state.players[self.seat];
+        let items = &state.data.items;
+        match goods {
+            Goods::Item(id) => match items.item(id) {
+                Some(item) => match item.slot {
+                    Some(slot) => {
+                        let owned = player
+                            .equipment
+                            .get(&slot)
+                            .into_iter()
+                            .chain(player.inventory.iter())
+                            .filter_map(|owned| items.item(owned))
+                            .filter(|owned| owned.slot == Some(slot))
+                            .map(item_power)
+                            .max()
+                            .unwrap_or_default();
+                        (item_power(item) - owned).max(0) * 2
+                    }
+                    None => {
+                        let healing = player
+                            .inventory
+                            .iter()
+                            .filter_map(|owned| items.item(owned))
+                            .filter(|owned| owned.heal > 0)
+                            .count();
+                        if healing < 2 {
+                            item.heal as i32 / 2
+                        } else {
+                            0
+                        }
+                    }
+                },
+                None => 0,
+            },
+            Goods::Rune(id) => match state.data.magic.rune(id) {
+                Some(rune)
+                    if rune.key
+                        && state.rules.victory == Victory::KeyRunes
+                        && !player.runes.contains_key(id) =>
+                {
+                    10
+                }
+                Some(rune) => {
+                    let casts = player
+                        .spellbook
+                        .iter()
+                        .filter_map(|spell| state.data.magic.spell(spell))
+                        .any(|spell| spell.cost.contains_key(id));
+                    let owned = player.runes.get(id).copied().unwrap_or_default();
+                    rune.attack + rune.defense + if casts && owned < 2 { 2 } else { 0 }
+                }
+                None => 0,
+            },
+            Goods::Upgrade(upgrade) => match upgrade {
+                Upgrade::AttackDie | Upgrade::DefenseDie => 8,
+                Upgrade::AttackBonus(bonus) | Upgrade::DefenseBonus(bonus) => bonus * 2,
+                Upgrade::MaxHealth(health) => *health as i32,
+            },
+        }
+    }
+}
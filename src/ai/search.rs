@@ -0,0 +1,305 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::{
+    cmp::Reverse,
+    time::{Duration, Instant},
+};
+
+use rand::RngCore;
+
+use super::{Brain, Difficulty};
+use crate::{
+    dice::DicePool,
+    rng::GameRng,
+    rules::{Command, Fighter, Goods, MatchState, Phase, Player, SpellTiming, Victory},
+};
+
+// what a won or lost match is worth against any position still being played
+const WIN: i32 = 10_000;
+// a rollout that never ends still has to stop somewhere
+const MAX_COMMANDS: u32 = 200;
+
+// how far the hard brain plays each choice out before picking one
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Search {
+    // how many times every choice is played out, each time with other dice
+    pub rollouts: u32,
+    // rounds played after the choice before the position is judged
+    pub rounds: u32,
+    // once spent no more rollouts start, at least one always does, the same match then
+    // searches differently on a slower machine
+    pub budget: Option<Duration>,
+    // out of 100 rollouts, how many more another choice has to do better than worse
+    // than the hunch to overrule it
+    pub margin: u32,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self {
+            rollouts: 24,
+            rounds: 2,
+            budget: None,
+            margin: 25,
+        }
+    }
+}
+
+impl Search {
+    // plays every candidate out with the rules against the hunch, every candidate gets the
+    // same dice in each rollout so only the choice makes the difference, and what counts is
+    // how often it does better rather than by how much, so a lucky win can't sway it
+    pub fn best(
+        &self,
+        state: &MatchState,
+        seat: usize,
+        hunch: Command,
+        rng: &mut GameRng,
+    ) -> Option<Command> {
+        let mut candidates = candidates(state, seat);
+        candidates.retain(|command| *command != hunch);
+        candidates.insert(0, hunch);
+        if candidates.len() == 1 {
+            return candidates.pop();
+        }
+        // the clock is only read when asked to, without a budget the search is the same
+        // on every machine
+        let deadline = self.budget.map(|budget| Instant::now() + budget);
+        let mut better = vec![0_i32; candidates.len()];
+        let mut played = 0;
+        while played < self.rollouts {
+            let spent = deadline.is_some_and(|deadline| Instant::now() >= deadline);
+            if played > 0 && spent {
+                break;
+            }
+            let seed = rng.next_u64();
+            let outcomes = candidates
+                .iter()
+                .map(|command| rollout(state, seat, command.clone(), seed, self.rounds))
+                .collect::<Vec<_>>();
+            for (better, outcome) in better.iter_mut().zip(&outcomes) {
+                *better += (*outcome - outcomes[0]).signum();
+            }
+            played += 1;
+        }
+        let needed = (played * self.margin) as i32 / 100;
+        candidates
+            .into_iter()
+            .zip(better)
+            .enumerate()
+            .filter(|(index, (_, better))| *index == 0 || *better > needed)
+            .max_by_key(|(index, (_, better))| (*better, Reverse(*index)))
+            .map(|(_, (command, _))| command)
+    }
+}
+
+// the choices worth searching for the phase, using and equipping items is left to the
+// brain as there is little to gain from playing those out, the first one is always a
+// command the rules take
+pub fn candidates(state: &MatchState, seat: usize) -> Vec<Command> {
+    if state.current != seat {
+        return Vec::new();
+    }
+    let player = &state.players[seat];
+    let casts = |timing: SpellTiming| {
+        state
+            .castable_spells()
+            .into_iter()
+            .filter(move |spell| spell.effect.timing() == timing)
+            .flat_map(|spell| {
+                let targets = if spell.effect.needs_target() {
+                    state
+                        .players
+                        .iter()
+                        .filter(|other| other.seat != seat && !other.out)
+                        .map(|other| Some(other.seat))
+                        .collect()
+                } else {
+                    vec![None]
+                };
+                targets.into_iter().map(|target| Command::Cast {
+                    spell: spell.id.clone(),
+                    target,
+                })
+            })
+    };
+    match &state.phase {
+        Phase::Roll => [Command::Roll]
+            .into_iter()
+            .chain(casts(SpellTiming::BeforeRoll))
+            .collect(),
+        Phase::Rolled { .. } => [Command::Move]
+            .into_iter()
+            .chain(casts(SpellTiming::AfterRoll))
+            .collect(),
+        Phase::Choose { options, .. } => options.iter().copied().map(Command::Choose).collect(),
+        Phase::Battle { .. } => vec![Command::Fight, Command::Flee],
+        Phase::Shop { tile } => {
+            let room = player.inventory.len() < state.data.items.inventory_slots;
+            let buys = state.shop_at(*tile).into_iter().flat_map(|(shop, def)| {
+                def.stock
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(index, entry)| {
+                        let price = state.shop_price(*tile, index)?;
+                        let fits = room || !matches!(entry.goods, Goods::Item(_));
+                        (shop.quantities[index] > 0 && fits && price <= player.gold)
+                            .then_some(Command::Buy(index))
+                    })
+            });
+            [Command::LeaveShop].into_iter().chain(buys).collect()
+        }
+        Phase::Over { .. } => Vec::new(),
+    }
+}
+
+// plays the match on from the command with the hard heuristics in every seat
+fn rollout(state: &MatchState, seat: usize, command: Command, seed: u64, rounds: u32) -> i32 {
+    let mut state = state.clone();
+    state.rng = GameRng::from_seed(seed);
+    if state.apply(command).is_err() {
+        return -2 * WIN;
+    }
+    let mut policy = (0..state.players.len())
+        .map(|other| Brain::new(other, Difficulty::Hard, seed).with_search(None))
+        .collect::<Vec<_>>();
+    let last_turn = state.turn + rounds;
+    for _ in 0..MAX_COMMANDS {
+        if state.winner().is_some() || state.turn > last_turn {
+            break;
+        }
+        let Some(command) = policy[state.current].decide(&state) else {
+            break;
+        };
+        if state.apply(command).is_err() {
+            break;
+        }
+    }
+    outcome(&state, seat)
+}
+
+// how the seat is doing against the best of the others
+pub fn outcome(state: &MatchState, seat: usize) -> i32 {
+    match state.winner() {
+        Some(winner) if winner == seat => return WIN,
+        Some(_) => return -WIN,
+        None => {}
+    }
+    let rival = state
+        .players
+        .iter()
+        .filter(|other| other.seat != seat && !other.out)
+        .map(|other| standing(state, other))
+        .max()
+        .unwrap_or_default();
+    standing(state, &state.players[seat]) - rival
+}
+
+// the score of the victory condition first, then what helps getting more of it
+fn standing(state: &MatchState, player: &Player) -> i32 {
+    if player.out {
+        return -WIN;
+    }
+    let weight = match state.rules.victory {
+        Victory::KeyRunes => 40,
+        Victory::Gold | Victory::TurnLimit => 2,
+        Victory::LastStanding => 30,
+    };
+    let average = |pool: DicePool| (pool.min() + pool.max()) / 2;
+    let combatant = state.combatant(Fighter::Player(player.seat));
+    let power = average(combatant.attack_pool()) + average(combatant.defense_pool());
+    state.score(player) as i32 * weight
+        + player.health as i32
+        + player.gold as i32 / 2
+        + player.rune_count() as i32 * 2
+        + power
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        board::Board,
+        rules::{GameData, MatchRules},
+    };
+
+    fn state(seed: u64) -> MatchState {
+        let assets = concat!(env!("CARGO_MANIFEST_DIR"), "/assets");
+        let board = Board::load(format!("{}/boards/classic.board.ron", assets)).unwrap();
+        let data = GameData::load(format!("{}/data", assets), "default").unwrap();
+        let names = ["One".to_string(), "Two".to_string(), "Three".to_string()];
+        MatchState::new(board, Arc::new(data), MatchRules::default(), &names, seed)
+    }
+
+    // with the time spent before the first rollout the search still answers something the
+    // rules take
+    #[test]
+    fn spent_budget_still_answers() {
+        for budget in [Duration::ZERO, Duration::from_micros(1)] {
+            let search = Search {
+                budget: Some(budget),
+                ..Search::default()
+            };
+            let mut battle = state(5);
+            battle.phase = Phase::Battle {
+                attacker: Fighter::Player(0),
+                defender: Fighter::Player(1),
+            };
+            let mut rng = GameRng::from_seed(5);
+            let command = search.best(&battle, 0, Command::Fight, &mut rng).unwrap();
+            assert!(battle.clone().apply(command).is_ok());
+
+            let mut rolled = state(6);
+            rolled.apply(Command::Roll).unwrap();
+            let command = search.best(&rolled, 0, Command::Move, &mut rng).unwrap();
+            assert!(rolled.clone().apply(command).is_ok());
+        }
+    }
+
+    // without a budget the same match searched twice from the same seed picks the same
+    // commands
+    #[test]
+    fn same_seed_same_choices() {
+        let play = || {
+            let mut state = state(11);
+            let mut brains = (0..state.players.len())
+                .map(|seat| {
+                    Brain::new(seat, Difficulty::Hard, 11).with_search(Some(Search::default()))
+                })
+                .collect::<Vec<_>>();
+            let mut commands = Vec::new();
+            while commands.len() < 30 {
+                let Some(command) = brains[state.current].decide(&state) else {
+                    break;
+                };
+                state.apply(command.clone()).unwrap();
+                commands.push(command);
+            }
+            (commands, state.checksum())
+        };
+        assert_eq!(play(), play());
+    }
+}
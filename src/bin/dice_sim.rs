@@ -0,0 +1,506 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::{
+    env,
+    error::Error,
+    fmt::Write as _,
+    fs,
+    path::PathBuf,
+    process,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use dice_master::{
+    ai::{Brain, Difficulty, Search},
+    board::{self, Board, GeneratorSettings},
+    rng::GameRng,
+    rules::{GameData, GameEvent, Goods, MatchRules, MatchState, Replay, Victory},
+};
+
+type SimError = Box<dyn Error + Send + Sync>;
+
+const USAGE: &str = "usage: dice_sim [options]
+
+  --matches N         matches to play (1000)
+  --seed N            seed of the first match, the others follow it (0)
+  --seats LIST        difficulties by seat, comma separated (normal,normal)
+  --victory NAME      key-runes, gold, last-standing or turn-limit (key-runes)
+  --gold-target N     gold to win a gold match (50)
+  --turn-limit N      rounds in a turn limit match (20)
+  --lives N           lives in a last standing match (3)
+  --max-turns N       rounds before an unfinished match is given up (200)
+  --board PATH        board file, or random for a new board every match
+                      (assets/boards/classic.board.ron)
+  --data FOLDER       folder of the data files (assets/data)
+  --set NAME          data set to load from the folder (default)
+  --threads N         matches played at once (every core)
+  --csv PATH          per seat stats of every match as CSV, - for stdout
+  --json PATH         stats of every match as JSON, - for stdout
+  --replays FOLDER    keeps every match as a replay in the folder
+  --verify PATH       plays the replay again and checks it ends the same, can be
+                      given more than once, no matches are played then
+  --help              this help
+
+without --csv or --json the CSV goes to stdout, a summary always goes to stderr";
+
+// how the matches are set up, from the command line
+struct Options {
+    matches: u32,
+    seed: u64,
+    seats: Vec<Difficulty>,
+    rules: MatchRules,
+    max_turns: u32,
+    board: Option<PathBuf>,
+    data: PathBuf,
+    set: String,
+    threads: usize,
+    csv: Option<String>,
+    json: Option<String>,
+    replays: Option<PathBuf>,
+    verify: Vec<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            matches: 1000,
+            seed: 0,
+            seats: vec![Difficulty::Normal, Difficulty::Normal],
+            rules: MatchRules::default(),
+            max_turns: 200,
+            board: Some("assets/boards/classic.board.ron".into()),
+            data: "assets/data".into(),
+            set: "default".into(),
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            csv: None,
+            json: None,
+            replays: None,
+            verify: Vec::new(),
+        }
+    }
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, SimError> {
+        let mut options = Self::default();
+        while let Some(arg) = args.next() {
+            if arg == "--help" {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("{} needs a value", arg))?;
+            match arg.as_str() {
+                "--matches" => options.matches = value.parse()?,
+                "--seed" => options.seed = value.parse()?,
+                "--seats" => {
+                    options.seats = value
+                        .split(',')
+                        .map(parse_difficulty)
+                        .collect::<Result<_, _>>()?
+                }
+                "--victory" => options.rules.victory = parse_victory(&value)?,
+                "--gold-target" => options.rules.gold_target = value.parse()?,
+                "--turn-limit" => options.rules.turn_limit = value.parse()?,
+                "--lives" => options.rules.lives = value.parse()?,
+                "--max-turns" => options.max_turns = value.parse()?,
+                "--board" if value == "random" => options.board = None,
+                "--board" => options.board = Some(value.into()),
+                "--data" => options.data = value.into(),
+                "--set" => options.set = value,
+                "--threads" => options.threads = value.parse::<usize>()?.max(1),
+                "--csv" => options.csv = Some(value),
+                "--json" => options.json = Some(value),
+                "--replays" => options.replays = Some(value.into()),
+                "--verify" => options.verify.push(value.into()),
+                _ => return Err(format!("unknown option {}\n\n{}", arg, USAGE).into()),
+            }
+        }
+        if options.seats.len() < 2 {
+            return Err("a match needs at least two seats".into());
+        }
+        if options.csv.is_none() && options.json.is_none() {
+            options.csv = Some("-".into());
+        }
+        if options.csv.as_deref() == Some("-") && options.json.as_deref() == Some("-") {
+            return Err(format!("--csv and --json can't both go to stdout\n\n{}", USAGE).into());
+        }
+        Ok(options)
+    }
+}
+
+fn parse_difficulty(name: &str) -> Result<Difficulty, String> {
+    match name.trim() {
+        "easy" => Ok(Difficulty::Easy),
+        "normal" => Ok(Difficulty::Normal),
+        "hard" => Ok(Difficulty::Hard),
+        other => Err(format!("unknown difficulty {}", other)),
+    }
+}
+
+fn parse_victory(name: &str) -> Result<Victory, String> {
+    match name {
+        "key-runes" => Ok(Victory::KeyRunes),
+        "gold" => Ok(Victory::Gold),
+        "last-standing" => Ok(Victory::LastStanding),
+        "turn-limit" => Ok(Victory::TurnLimit),
+        other => Err(format!("unknown victory {}", other)),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct MatchStats {
+    index: u32,
+    seed: u64,
+    board: String,
+    victory: Victory,
+    turns: u32,
+    // none when the match was given up
+    winner: Option<usize>,
+    seats: Vec<SeatStats>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct SeatStats {
+    seat: usize,
+    difficulty: Difficulty,
+    score: u32,
+    gold: u32,
+    // the gold at the start of every round
+    gold_curve: Vec<u32>,
+    runes_collected: u32,
+    runes_bought: u32,
+    runes_spent: u32,
+    runes_sold: u32,
+    spells_cast: u32,
+    battles_won: u32,
+    monsters_defeated: u32,
+    defeats: u32,
+    steps: u32,
+    out: bool,
+}
+
+impl MatchStats {
+    fn count(&mut self, event: &GameEvent, data: &GameData) {
+        match event {
+            GameEvent::RuneCollected { seat, .. } => self.seats[*seat].runes_collected += 1,
+            GameEvent::SpellCast { seat, spell, .. } => {
+                let stats = &mut self.seats[*seat];
+                stats.spells_cast += 1;
+                stats.runes_spent += data
+                    .magic
+                    .spell(spell)
+                    .map_or(0, |spell| spell.cost.values().sum());
+            }
+            GameEvent::Bought {
+                seat,
+                goods: Goods::Rune(_),
+                ..
+            } => self.seats[*seat].runes_bought += 1,
+            GameEvent::Sold {
+                seat,
+                goods: Goods::Rune(_),
+                ..
+            } => self.seats[*seat].runes_sold += 1,
+            _ => {}
+        }
+    }
+
+    fn record_round(&mut self, state: &MatchState) {
+        for (stats, player) in self.seats.iter_mut().zip(&state.players) {
+            stats.gold_curve.push(player.gold);
+        }
+    }
+
+    fn finish(&mut self, state: &MatchState) {
+        self.turns = state.turn;
+        self.winner = state.winner();
+        for (stats, player) in self.seats.iter_mut().zip(&state.players) {
+            stats.score = state.score(player);
+            stats.gold = player.gold;
+            stats.battles_won = player.record.battles_won;
+            stats.monsters_defeated = player.record.monsters_defeated;
+            stats.defeats = player.record.defeats;
+            stats.steps = player.record.steps;
+            stats.out = player.out;
+        }
+    }
+}
+
+fn play(
+    index: u32,
+    options: &Options,
+    board: Option<&Board>,
+    data: &Arc<GameData>,
+) -> Result<MatchStats, SimError> {
+    let seed = options.seed.wrapping_add(index as u64);
+    let board = match board {
+        Some(board) => board.clone(),
+        None => board::generate(
+            &GeneratorSettings::default(),
+            &mut GameRng::from_seed(seed),
+            &mut |_, _| {},
+        )?,
+    };
+    let names = options
+        .seats
+        .iter()
+        .enumerate()
+        .map(|(seat, difficulty)| format!("{} {}", difficulty.name(), seat + 1))
+        .collect::<Vec<_>>();
+    let mut brains = options
+        .seats
+        .iter()
+        .enumerate()
+        // every rollout is waited for, so a seed always plays the same match
+        .map(|(seat, difficulty)| {
+            let search = difficulty.search().map(|search| Search {
+                budget: None,
+                ..search
+            });
+            Brain::new(seat, *difficulty, seed).with_search(search)
+        })
+        .collect::<Vec<_>>();
+    let mut stats = MatchStats {
+        index,
+        seed,
+        board: board.name.clone(),
+        victory: options.rules.victory,
+        turns: 0,
+        winner: None,
+        seats: options
+            .seats
+            .iter()
+            .enumerate()
+            .map(|(seat, difficulty)| SeatStats {
+                seat,
+                difficulty: *difficulty,
+                ..Default::default()
+            })
+            .collect(),
+    };
+
+    let mut state = MatchState::new(board, data.clone(), options.rules, &names, seed);
+    let mut replay = options.replays.as_ref().map(|_| Replay::start(&state));
+    stats.record_round(&state);
+    while state.winner().is_none() && state.turn <= options.max_turns {
+        let Some(command) = brains[state.current].decide(&state) else {
+            break;
+        };
+        let round = state.turn;
+        let events = state
+            .apply(command.clone())
+            .map_err(|err| format!("match {}: {:?} rejected, {}", index, command, err))?;
+        if let Some(replay) = &mut replay {
+            replay.record(command, &state);
+        }
+        for event in &events {
+            stats.count(event, &state.data);
+        }
+        if state.turn != round {
+            stats.record_round(&state);
+        }
+    }
+    stats.finish(&state);
+    if let (Some(folder), Some(replay)) = (&options.replays, replay) {
+        replay.save(folder.join(format!("match-{}.replay.ron", seed)))?;
+    }
+    Ok(stats)
+}
+
+// every thread takes every nth match, the results are put back in order
+fn play_all(options: &Options, board: Option<&Board>, data: &Arc<GameData>) -> Vec<MatchStats> {
+    let threads = options.threads.min(options.matches.max(1) as usize);
+    let mut matches = thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|worker| {
+                scope.spawn(move || {
+                    (worker as u32..options.matches)
+                        .step_by(threads)
+                        .filter_map(|index| match play(index, options, board, data) {
+                            Ok(stats) => Some(stats),
+                            Err(err) => {
+                                eprintln!("dice_sim: {}", err);
+                                None
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("simulation thread panicked"))
+            .collect::<Vec<_>>()
+    });
+    matches.sort_by_key(|stats| stats.index);
+    matches
+}
+
+fn to_csv(matches: &[MatchStats]) -> String {
+    let mut csv = String::from(
+        "match,seed,board,victory,turns,winner,seat,difficulty,won,score,gold,runes_collected,\
+         runes_bought,runes_spent,runes_sold,spells_cast,battles_won,monsters_defeated,defeats,\
+         steps,out,gold_curve\n",
+    );
+    for stats in matches {
+        for seat in &stats.seats {
+            let curve = seat
+                .gold_curve
+                .iter()
+                .map(|gold| gold.to_string())
+                .collect::<Vec<_>>()
+                .join(";");
+            let _ = writeln!(
+                csv,
+                "{},{},\"{}\",{:?},{},{},{},{:?},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                stats.index,
+                stats.seed,
+                stats.board.replace('"', "\"\""),
+                stats.victory,
+                stats.turns,
+                stats
+                    .winner
+                    .map_or(String::new(), |winner| winner.to_string()),
+                seat.seat,
+                seat.difficulty,
+                stats.winner == Some(seat.seat),
+                seat.score,
+                seat.gold,
+                seat.runes_collected,
+                seat.runes_bought,
+                seat.runes_spent,
+                seat.runes_sold,
+                seat.spells_cast,
+                seat.battles_won,
+                seat.monsters_defeated,
+                seat.defeats,
+                seat.steps,
+                seat.out,
+                curve
+            );
+        }
+    }
+    csv
+}
+
+fn write(path: &str, text: &str) -> Result<(), SimError> {
+    if path == "-" {
+        print!("{}", text);
+    } else {
+        fs::write(path, text).map_err(|err| format!("can't write {}: {}", path, err))?;
+    }
+    Ok(())
+}
+
+// wins by seat and the length of the matches, so a run can be judged at a glance
+fn summary(options: &Options, matches: &[MatchStats], elapsed: Duration) -> String {
+    let mut summary = format!(
+        "{} matches of {} in {:.1}s",
+        matches.len(),
+        options.rules.victory.name(),
+        elapsed.as_secs_f32()
+    );
+    for (seat, difficulty) in options.seats.iter().enumerate() {
+        let wins = matches
+            .iter()
+            .filter(|stats| stats.winner == Some(seat))
+            .count();
+        let _ = write!(
+            summary,
+            "\n  seat {} ({}): {} wins, {:.1}%",
+            seat + 1,
+            difficulty.name(),
+            wins,
+            wins as f32 * 100. / matches.len().max(1) as f32
+        );
+    }
+    let unfinished = matches
+        .iter()
+        .filter(|stats| stats.winner.is_none())
+        .count();
+    let turns = matches.iter().map(|stats| stats.turns).sum::<u32>();
+    let _ = write!(
+        summary,
+        "\n  unfinished: {}\n  average rounds: {:.1}",
+        unfinished,
+        turns as f32 / matches.len().max(1) as f32
+    );
+    summary
+}
+
+// replays kept as fixtures have to keep ending the same as the rules change
+fn verify(paths: &[PathBuf]) -> Result<(), SimError> {
+    let mut failed = 0;
+    for path in paths {
+        match Replay::load(path).and_then(|replay| replay.verify().map(|_| replay)) {
+            Ok(replay) => eprintln!(
+                "{}: verified, {} commands",
+                path.display(),
+                replay.commands.len()
+            ),
+            Err(err) => {
+                eprintln!("{}: {}", path.display(), err);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(format!("{} of {} replays failed", failed, paths.len()).into());
+    }
+    Ok(())
+}
+
+fn run() -> Result<(), SimError> {
+    let options = Options::parse(env::args().skip(1))?;
+    if !options.verify.is_empty() {
+        return verify(&options.verify);
+    }
+    let data = Arc::new(GameData::load(&options.data, &options.set)?);
+    let board = options.board.as_ref().map(Board::load).transpose()?;
+
+    let started = Instant::now();
+    let matches = play_all(&options, board.as_ref(), &data);
+    if let Some(path) = &options.csv {
+        write(path, &to_csv(&matches))?;
+    }
+    if let Some(path) = &options.json {
+        write(path, &serde_json::to_string_pretty(&matches)?)?;
+    }
+    eprintln!("{}", summary(&options, &matches, started.elapsed()));
+    Ok(())
+}
+
+// plays computer against computer with the game's own rules and data, no window needed
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("dice_sim: {}", err);
+        process::exit(1);
+    }
+}
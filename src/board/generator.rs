@@ -0,0 +1,284 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::f32::consts::TAU;
+
+use rand::{seq::SliceRandom, Rng};
+
+use super::{Board, BoardError, Tile, TileId, TileKind};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorSettings {
+    pub loop_length: usize,
+    pub branches: usize,
+    pub branch_length: (usize, usize),
+    pub weights: Vec<(TileKind, u32)>,
+    pub required: Vec<(TileKind, usize)>,
+    pub radius: (f32, f32),
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            loop_length: 28,
+            branches: 2,
+            branch_length: (2, 4),
+            weights: vec![
+                (TileKind::Battle, 5),
+                (TileKind::Event, 4),
+                (TileKind::Rune, 3),
+                (TileKind::Town, 1),
+                (TileKind::Shrine, 1),
+            ],
+            required: vec![
+                (TileKind::Town, 2),
+                (TileKind::Shrine, 1),
+                (TileKind::Rune, 3),
+            ],
+            radius: (780., 400.),
+        }
+    }
+}
+
+const MIN_LOOP_LENGTH: usize = 8;
+// how far ahead on the loop a branch may rejoin, as a fraction of the loop
+const MAX_BRANCH_SPAN: usize = 3;
+// how much branches bend towards the centre of the board
+const BRANCH_BEND: f32 = 0.6;
+
+pub fn generate(
+    settings: &GeneratorSettings,
+    rng: &mut impl Rng,
+    progress: &mut dyn FnMut(u32, u32),
+) -> Result<Board, BoardError> {
+    let loop_length = settings.loop_length.max(MIN_LOOP_LENGTH);
+    let total = (loop_length + settings.branches + 2) as u32;
+    let mut done = 0;
+    let mut step = |progress: &mut dyn FnMut(u32, u32)| {
+        done += 1;
+        progress(done, total);
+    };
+
+    let (radius_x, radius_y) = settings.radius;
+    let mut tiles = Vec::with_capacity(loop_length);
+    for index in 0..loop_length {
+        // start on the top left and go clockwise
+        let angle = TAU * 3. / 8. - TAU * index as f32 / loop_length as f32;
+        tiles.push(Tile {
+            id: TileId(index),
+            kind: if index == 0 {
+                TileKind::Start
+            } else {
+                TileKind::Event
+            },
+            position: (radius_x * angle.cos(), radius_y * angle.sin()),
+            next: vec![TileId((index + 1) % loop_length)],
+        });
+        step(progress);
+    }
+
+    let mut forks: Vec<usize> = Vec::new();
+    for _ in 0..settings.branches {
+        let from = (1..loop_length)
+            .filter(|index| !forks.contains(index) && !forks.contains(&(index + 1)))
+            .collect::<Vec<_>>()
+            .choose(rng)
+            .copied();
+        if let Some(from) = from {
+            let span = rng.gen_range(3..=(loop_length / MAX_BRANCH_SPAN).max(3));
+            let to = TileId((from + span) % loop_length);
+            let (min_length, max_length) = settings.branch_length;
+            let length = rng.gen_range(min_length.max(1)..=max_length.max(min_length.max(1)));
+            add_branch(&mut tiles, TileId(from), to, length);
+            forks.push(from);
+        }
+        step(progress);
+    }
+
+    assign_kinds(&mut tiles, settings, &forks, rng)?;
+    step(progress);
+
+    let board = Board {
+        name: "Random".into(),
+        tiles,
+    };
+    board.validate()?;
+    step(progress);
+    Ok(board)
+}
+
+fn add_branch(tiles: &mut Vec<Tile>, from: TileId, to: TileId, length: usize) {
+    let start = tiles[from.0].position;
+    let end = tiles[to.0].position;
+    let control = (
+        (start.0 + end.0) / 2. * (1. - BRANCH_BEND),
+        (start.1 + end.1) / 2. * (1. - BRANCH_BEND),
+    );
+    let first = tiles.len();
+    for step in 0..length {
+        let t = (step + 1) as f32 / (length + 1) as f32;
+        let id = TileId(first + step);
+        let next = if step + 1 == length {
+            to
+        } else {
+            TileId(id.0 + 1)
+        };
+        tiles.push(Tile {
+            id,
+            kind: TileKind::Event,
+            position: (
+                bezier(start.0, control.0, end.0, t),
+                bezier(start.1, control.1, end.1, t),
+            ),
+            next: vec![next],
+        });
+    }
+    tiles[from.0].next.push(TileId(first));
+}
+
+fn bezier(start: f32, control: f32, end: f32, t: f32) -> f32 {
+    let u = 1. - t;
+    u * u * start + 2. * u * t * control + t * t * end
+}
+
+// the required tiles go first, a board without room for all of them is an error rather
+// than a board short of towns
+fn assign_kinds(
+    tiles: &mut [Tile],
+    settings: &GeneratorSettings,
+    forks: &[usize],
+    rng: &mut impl Rng,
+) -> Result<(), BoardError> {
+    for fork in forks {
+        tiles[*fork].kind = TileKind::Shortcut;
+    }
+
+    let mut free: Vec<usize> = (1..tiles.len())
+        .filter(|index| !forks.contains(index))
+        .collect();
+    free.shuffle(rng);
+
+    for (kind, count) in &settings.required {
+        for placed in 0..*count {
+            let Some(index) = free.pop() else {
+                return Err(BoardError::NoRoom {
+                    kind: *kind,
+                    missing: count - placed,
+                });
+            };
+            tiles[index].kind = *kind;
+        }
+    }
+
+    for index in free {
+        if let Ok((kind, _)) = settings.weights.choose_weighted(rng, |(_, weight)| *weight) {
+            tiles[index].kind = *kind;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::GameRng;
+
+    const SEEDS: u64 = 300;
+
+    fn settings() -> Vec<GeneratorSettings> {
+        let default = GeneratorSettings::default();
+        vec![
+            GeneratorSettings {
+                loop_length: 0,
+                branches: 0,
+                ..default.clone()
+            },
+            GeneratorSettings {
+                loop_length: 12,
+                branches: 4,
+                branch_length: (1, 1),
+                ..default.clone()
+            },
+            GeneratorSettings {
+                loop_length: 40,
+                branches: 6,
+                branch_length: (3, 8),
+                required: vec![(TileKind::Town, 4), (TileKind::Rune, 8)],
+                ..default.clone()
+            },
+            default,
+        ]
+    }
+
+    #[test]
+    fn every_board_is_valid_and_connected() {
+        for settings in settings() {
+            for seed in 0..SEEDS {
+                let mut rng = GameRng::from_seed(seed);
+                let board = generate(&settings, &mut rng, &mut |_, _| {})
+                    .unwrap_or_else(|err| panic!("seed {} {:?}: {}", seed, settings, err));
+                assert!(board.validate().is_ok(), "seed {}", seed);
+                let distances = board.distances_from(board.start());
+                assert!(
+                    distances.iter().all(Option::is_some),
+                    "seed {} has tiles out of reach",
+                    seed
+                );
+                for (kind, count) in &settings.required {
+                    assert!(
+                        board.tiles_of(*kind).count() >= *count,
+                        "seed {} is short of {:?}",
+                        seed,
+                        kind
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_board() {
+        let settings = GeneratorSettings::default();
+        let first = generate(&settings, &mut GameRng::from_seed(7), &mut |_, _| {}).unwrap();
+        let second = generate(&settings, &mut GameRng::from_seed(7), &mut |_, _| {}).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn required_tiles_without_room_is_an_error() {
+        let settings = GeneratorSettings {
+            loop_length: 8,
+            branches: 0,
+            required: vec![(TileKind::Town, 20)],
+            ..GeneratorSettings::default()
+        };
+        let result = generate(&settings, &mut GameRng::from_seed(1), &mut |_, _| {});
+        assert!(matches!(
+            result,
+            Err(BoardError::NoRoom {
+                kind: TileKind::Town,
+                missing: 13
+            })
+        ));
+    }
+}
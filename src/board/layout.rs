@@ -0,0 +1,223 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::{collections::VecDeque, fmt, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Tile, TileId, TileKind};
+
+#[derive(Debug)]
+pub enum BoardError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Empty,
+    MisplacedTile { index: usize, id: TileId },
+    UnknownTile { from: TileId, to: TileId },
+    DeadEnd(TileId),
+    StartCount(usize),
+    Unreachable(TileId),
+    NoWayBack(TileId),
+    NoRoom { kind: TileKind, missing: usize },
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::Io(err) => write!(f, "can't read board: {}", err),
+            BoardError::Parse(err) => write!(f, "can't parse board: {}", err),
+            BoardError::Empty => write!(f, "board has no tiles"),
+            BoardError::MisplacedTile { index, id } => {
+                write!(f, "tile {} is in position {}", id, index)
+            }
+            BoardError::UnknownTile { from, to } => {
+                write!(f, "tile {} connects to unknown tile {}", from, to)
+            }
+            BoardError::DeadEnd(id) => write!(f, "tile {} has no way forward", id),
+            BoardError::StartCount(count) => {
+                write!(f, "board should have one start tile but has {}", count)
+            }
+            BoardError::Unreachable(id) => write!(f, "tile {} can't be reached from start", id),
+            BoardError::NoWayBack(id) => write!(f, "start can't be reached from tile {}", id),
+            BoardError::NoRoom { kind, missing } => {
+                write!(f, "no room left for {} more {} tiles", missing, kind.name())
+            }
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+impl From<io::Error> for BoardError {
+    fn from(err: io::Error) -> Self {
+        BoardError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for BoardError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        BoardError::Parse(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Board {
+    pub name: String,
+    pub tiles: Vec<Tile>,
+}
+
+impl Board {
+    pub fn from_ron(text: &str) -> Result<Self, BoardError> {
+        let board: Board = ron::from_str(text)?;
+        board.validate()?;
+        Ok(board)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BoardError> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    pub fn tile(&self, id: TileId) -> &Tile {
+        &self.tiles[id.0]
+    }
+
+    pub fn next(&self, id: TileId) -> &[TileId] {
+        &self.tile(id).next
+    }
+
+    pub fn start(&self) -> TileId {
+        self.tiles
+            .iter()
+            .find(|tile| tile.kind == TileKind::Start)
+            .map(|tile| tile.id)
+            .expect("a valid board has a start tile")
+    }
+
+    pub fn tiles_of(&self, kind: TileKind) -> impl Iterator<Item = &Tile> {
+        self.tiles.iter().filter(move |tile| tile.kind == kind)
+    }
+
+    // breadth first distances in steps, None for tiles that can't be reached
+    pub fn distances_from(&self, from: TileId) -> Vec<Option<u32>> {
+        let mut distances = vec![None; self.tiles.len()];
+        let mut queue = VecDeque::new();
+        distances[from.0] = Some(0);
+        queue.push_back(from);
+        while let Some(id) = queue.pop_front() {
+            let distance = distances[id.0].unwrap_or_default();
+            for next in self.next(id) {
+                if distances[next.0].is_none() {
+                    distances[next.0] = Some(distance + 1);
+                    queue.push_back(*next);
+                }
+            }
+        }
+        distances
+    }
+
+    pub fn validate(&self) -> Result<(), BoardError> {
+        if self.tiles.is_empty() {
+            return Err(BoardError::Empty);
+        }
+        for (index, tile) in self.tiles.iter().enumerate() {
+            if tile.id.0 != index {
+                return Err(BoardError::MisplacedTile { index, id: tile.id });
+            }
+            if tile.next.is_empty() {
+                return Err(BoardError::DeadEnd(tile.id));
+            }
+            if let Some(to) = tile.next.iter().find(|to| to.0 >= self.tiles.len()) {
+                return Err(BoardError::UnknownTile {
+                    from: tile.id,
+                    to: *to,
+                });
+            }
+        }
+        let starts = self.tiles_of(TileKind::Start).count();
+        if starts != 1 {
+            return Err(BoardError::StartCount(starts));
+        }
+        let distances = self.distances_from(self.start());
+        if let Some(tile) = self
+            .tiles
+            .iter()
+            .find(|tile| distances[tile.id.0].is_none())
+        {
+            return Err(BoardError::Unreachable(tile.id));
+        }
+        // players go round the board, so from any tile we should be able to pass start again
+        let start = self.start();
+        if let Some(tile) = self
+            .tiles
+            .iter()
+            .find(|tile| self.distances_from(tile.id)[start.0].is_none())
+        {
+            return Err(BoardError::NoWayBack(tile.id));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classic() -> Board {
+        let assets = concat!(env!("CARGO_MANIFEST_DIR"), "/assets");
+        Board::load(format!("{}/boards/classic.board.ron", assets)).unwrap()
+    }
+
+    #[test]
+    fn classic_board_loads() {
+        let board = classic();
+        assert_eq!(board.start(), TileId(0));
+        assert!(board.tiles_of(TileKind::Town).count() > 0);
+    }
+
+    #[test]
+    fn dangling_link_is_refused() {
+        let mut board = classic();
+        let last = board.tiles.len() - 1;
+        board.tiles[last].next = vec![TileId(board.tiles.len())];
+        assert!(matches!(
+            board.validate(),
+            Err(BoardError::UnknownTile { from, to }) if from == TileId(last) && to.0 == last + 1
+        ));
+    }
+
+    #[test]
+    fn missing_start_is_refused() {
+        let mut board = classic();
+        board.tiles[0].kind = TileKind::Event;
+        assert!(matches!(board.validate(), Err(BoardError::StartCount(0))));
+        let text = ron::to_string(&board).unwrap();
+        assert!(matches!(
+            Board::from_ron(&text),
+            Err(BoardError::StartCount(0))
+        ));
+    }
+}
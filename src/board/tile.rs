@@ -0,0 +1,75 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct TileId(pub usize);
+
+impl fmt::Display for TileId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub enum TileKind {
+    Start,
+    Town,
+    Shrine,
+    Battle,
+    Rune,
+    Event,
+    Shortcut,
+}
+
+impl TileKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TileKind::Start => "Start",
+            TileKind::Town => "Town",
+            TileKind::Shrine => "Shrine",
+            TileKind::Battle => "Battle",
+            TileKind::Rune => "Rune",
+            TileKind::Event => "Event",
+            TileKind::Shortcut => "Shortcut",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Tile {
+    pub id: TileId,
+    pub kind: TileKind,
+    pub position: (f32, f32),
+    pub next: Vec<TileId>,
+}
+
+impl Tile {
+    pub fn is_fork(&self) -> bool {
+        self.next.len() > 1
+    }
+}
@@ -0,0 +1,251 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use super::DicePool;
+
+// the chance of every total, kept normalised so even pools of dozens of dice are worked out
+// without counting ways that would not fit in any integer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    lowest: i32,
+    chances: Vec<f64>,
+}
+
+impl Distribution {
+    // a total that always comes up
+    pub fn constant(value: i32) -> Self {
+        Self {
+            lowest: value,
+            chances: vec![1.],
+        }
+    }
+
+    // every face of a single die is as likely
+    pub fn die(sides: u8) -> Self {
+        let sides = sides.max(1) as usize;
+        Self {
+            lowest: 1,
+            chances: vec![1. / sides as f64; sides],
+        }
+    }
+
+    pub fn highest(&self) -> i32 {
+        self.lowest + self.chances.len() as i32 - 1
+    }
+
+    // the totals with a chance of coming up, lowest first
+    pub fn totals(&self) -> impl Iterator<Item = (i32, f64)> + '_ {
+        self.chances
+            .iter()
+            .enumerate()
+            .filter(|(_, chance)| **chance > 0.)
+            .map(|(index, chance)| (self.lowest + index as i32, *chance))
+    }
+
+    pub fn chance(&self, value: i32) -> f64 {
+        usize::try_from(value - self.lowest)
+            .ok()
+            .and_then(|index| self.chances.get(index))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn at_least(&self, value: i32) -> f64 {
+        self.totals()
+            .filter(|(total, _)| *total >= value)
+            .map(|(_, chance)| chance)
+            .sum()
+    }
+
+    // both rolled together and added, each pair of totals weighed by how likely both are
+    pub fn plus(&self, other: &Distribution) -> Self {
+        let mut chances = vec![0.; self.chances.len() + other.chances.len() - 1];
+        for (index, mine) in self.chances.iter().enumerate() {
+            for (other_index, theirs) in other.chances.iter().enumerate() {
+                chances[index + other_index] += mine * theirs;
+            }
+        }
+        Self {
+            lowest: self.lowest + other.lowest,
+            chances,
+        }
+    }
+
+    pub fn minus(&self, other: &Distribution) -> Self {
+        self.plus(&other.negated())
+    }
+
+    pub fn negated(&self) -> Self {
+        Self {
+            lowest: -self.highest(),
+            chances: self.chances.iter().rev().copied().collect(),
+        }
+    }
+
+    pub fn shifted(&self, by: i32) -> Self {
+        Self {
+            lowest: self.lowest + by,
+            chances: self.chances.clone(),
+        }
+    }
+
+    // every total under the floor counts as the floor, as damage never goes below zero
+    pub fn floored(&self, floor: i32) -> Self {
+        if floor <= self.lowest {
+            return self.clone();
+        }
+        if floor >= self.highest() {
+            return Self::constant(floor);
+        }
+        let cut = (floor - self.lowest) as usize;
+        let below = self.chances[..=cut].iter().sum();
+        Self {
+            lowest: floor,
+            chances: [below]
+                .into_iter()
+                .chain(self.chances[cut + 1..].iter().copied())
+                .collect(),
+        }
+    }
+}
+
+impl DicePool {
+    // convolves one die at a time, so even big pools are counted without listing every roll
+    pub fn distribution(&self) -> Distribution {
+        let die = Distribution::die(self.sides);
+        (0..self.dice)
+            .fold(Distribution::constant(0), |total, _| total.plus(&die))
+            .shifted(self.bonus)
+    }
+}
+
+// "37%" with anything that can happen but rounds away shown as under one percent
+pub fn percent(chance: f64) -> String {
+    if chance > 0. && chance < 0.005 {
+        "<1%".into()
+    } else if chance < 1. && chance > 0.995 {
+        ">99%".into()
+    } else {
+        format!("{:.0}%", chance * 100.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    // every total of every way the dice can fall, listed one by one
+    fn brute_force(pools: &[DicePool], combine: impl Fn(&[i32]) -> i32) -> Vec<(i32, f64)> {
+        let mut rolls = vec![Vec::new()];
+        for pool in pools {
+            let mut next = Vec::new();
+            for roll in &rolls {
+                let mut totals = vec![pool.bonus];
+                for _ in 0..pool.dice {
+                    totals = totals
+                        .iter()
+                        .flat_map(|total| (1..=pool.sides as i32).map(move |face| total + face))
+                        .collect();
+                }
+                for total in totals {
+                    let mut roll: Vec<i32> = roll.clone();
+                    roll.push(total);
+                    next.push(roll);
+                }
+            }
+            rolls = next;
+        }
+        let mut counts = std::collections::BTreeMap::new();
+        for roll in &rolls {
+            *counts.entry(combine(roll)).or_insert(0) += 1;
+        }
+        counts
+            .into_iter()
+            .map(|(total, count)| (total, count as f64 / rolls.len() as f64))
+            .collect()
+    }
+
+    fn assert_matches(distribution: &Distribution, expected: &[(i32, f64)]) {
+        let found = distribution.totals().collect::<Vec<_>>();
+        assert_eq!(found.len(), expected.len(), "{:?}", found);
+        for ((total, chance), (expected_total, expected_chance)) in found.iter().zip(expected) {
+            assert_eq!(total, expected_total);
+            assert!((chance - expected_chance).abs() < EPSILON, "{}", total);
+        }
+    }
+
+    #[test]
+    fn two_d6_has_the_36_way_table() {
+        let distribution = DicePool::new(2, 6).distribution();
+        let ways = [1, 2, 3, 4, 5, 6, 5, 4, 3, 2, 1];
+        for (total, ways) in (2..=12).zip(ways) {
+            assert!((distribution.chance(total) - ways as f64 / 36.).abs() < EPSILON);
+        }
+        assert_eq!(distribution.chance(1), 0.);
+        assert_eq!(distribution.chance(13), 0.);
+    }
+
+    #[test]
+    fn three_d6_reaches_twelve_three_times_in_eight() {
+        let distribution = DicePool::new(3, 6).distribution();
+        assert!((distribution.at_least(12) - 0.375).abs() < EPSILON);
+    }
+
+    #[test]
+    fn bonus_shifts_every_total() {
+        let pool = DicePool::new(2, 4).with_bonus(3);
+        assert_matches(
+            &pool.distribution(),
+            &brute_force(&[pool], |totals| totals[0]),
+        );
+    }
+
+    #[test]
+    fn minus_negated_and_floored_match_every_roll() {
+        let attack = DicePool::new(2, 6).with_bonus(1);
+        let defense = DicePool::new(1, 8);
+        let (a, d) = (attack.distribution(), defense.distribution());
+        let pools = [attack, defense];
+        assert_matches(&a.negated(), &brute_force(&pools[..1], |totals| -totals[0]));
+        assert_matches(
+            &a.minus(&d),
+            &brute_force(&pools, |totals| totals[0] - totals[1]),
+        );
+        for floor in [-20, -3, 0, 4, 13, 20] {
+            assert_matches(
+                &a.minus(&d).floored(floor),
+                &brute_force(&pools, |totals| (totals[0] - totals[1]).max(floor)),
+            );
+        }
+    }
+
+    #[test]
+    fn big_pools_stay_normalised() {
+        let distribution = DicePool::new(60, 20).distribution();
+        let total = distribution.totals().map(|(_, chance)| chance).sum::<f64>();
+        assert!((total - 1.).abs() < 1e-6);
+        assert!((distribution.at_least(60 * 21 / 2) - 0.5).abs() < 0.05);
+    }
+}
@@ -0,0 +1,114 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::fmt;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct DicePool {
+    pub dice: u8,
+    pub sides: u8,
+    #[serde(default)]
+    pub bonus: i32,
+}
+
+impl DicePool {
+    pub const fn new(dice: u8, sides: u8) -> Self {
+        Self {
+            dice,
+            sides,
+            bonus: 0,
+        }
+    }
+
+    pub const fn with_bonus(self, bonus: i32) -> Self {
+        Self { bonus, ..self }
+    }
+
+    pub fn min(&self) -> i32 {
+        self.dice as i32 + self.bonus
+    }
+
+    pub fn max(&self) -> i32 {
+        self.dice as i32 * self.sides as i32 + self.bonus
+    }
+
+    pub fn roll(&self, rng: &mut impl Rng) -> Roll {
+        Roll {
+            faces: (0..self.dice)
+                .map(|_| rng.gen_range(1..=self.sides.max(1)))
+                .collect(),
+            bonus: self.bonus,
+        }
+    }
+}
+
+impl fmt::Display for DicePool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}d{}", self.dice, self.sides)?;
+        match self.bonus {
+            0 => Ok(()),
+            bonus if bonus > 0 => write!(f, "+{}", bonus),
+            bonus => write!(f, "{}", bonus),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Roll {
+    pub faces: Vec<u8>,
+    pub bonus: i32,
+}
+
+impl Roll {
+    pub fn total(&self) -> i32 {
+        self.faces.iter().map(|face| *face as i32).sum::<i32>() + self.bonus
+    }
+}
+
+// reads as "3 and 5, total 8" so it can be announced as it is
+impl fmt::Display for Roll {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let faces = self
+            .faces
+            .iter()
+            .map(|face| face.to_string())
+            .collect::<Vec<_>>();
+        match faces.split_last() {
+            None => write!(f, "nothing")?,
+            Some((last, [])) => write!(f, "{}", last)?,
+            Some((last, rest)) => write!(f, "{} and {}", rest.join(", "), last)?,
+        }
+        match self.bonus {
+            0 => {}
+            bonus if bonus > 0 => write!(f, " plus {}", bonus)?,
+            bonus => write!(f, " minus {}", -bonus)?,
+        }
+        if self.faces.len() > 1 || self.bonus != 0 {
+            write!(f, ", total {}", self.total())?;
+        }
+        Ok(())
+    }
+}
@@ -21,7 +21,8 @@ OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 ***/
 
-use crate::game::State;
+use super::flip;
+use crate::game::{Config, State};
 use bevy::prelude::*;
 use bevy_tweening::*;
 use std::time::Duration;
@@ -34,7 +35,9 @@ pub struct Handler;
 
 impl Plugin for Handler {
     fn build(&self, app: &mut App) {
-        app.add_system(go_to_state).add_system(rotate_items);
+        app.add_system(go_to_state)
+            .add_system(rotate_items)
+            .add_system(flip::turn_over);
     }
 }
 
@@ -95,7 +98,14 @@ impl Rotate {
     }
 }
 
-fn rotate_items(mut q_item: Query<(&Rotate, &mut Transform)>, time: Res<Time>) {
+fn rotate_items(
+    mut q_item: Query<(&Rotate, &mut Transform)>,
+    time: Res<Time>,
+    config: Res<Config>,
+) {
+    if config.reduced_motion {
+        return;
+    }
     for (rotation, mut transform) in q_item.iter_mut() {
         transform.rotate_z(rotation.speed * time.delta_seconds());
     }
@@ -0,0 +1,80 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_tweening::{lens::TransformScaleLens, *};
+
+// tween events are shared by every animator, this one needs to be unique
+const FLIP_HALFWAY: u64 = 0xF11B;
+
+// swaps which child is shown when the flip is edge on
+#[derive(Component, Debug)]
+pub struct Flippable {
+    pub back: Entity,
+    pub front: Entity,
+}
+
+pub fn flip(millis: u64) -> Sequence<Transform> {
+    let edge_on = Vec3::new(0., 1., 1.);
+    let fold = Tween::new(
+        EaseFunction::QuadraticIn,
+        Duration::from_millis(millis / 2),
+        TransformScaleLens {
+            start: Vec3::ONE,
+            end: edge_on,
+        },
+    )
+    .with_completed_event(FLIP_HALFWAY);
+    let unfold = Tween::new(
+        EaseFunction::QuadraticOut,
+        Duration::from_millis(millis / 2),
+        TransformScaleLens {
+            start: edge_on,
+            end: Vec3::ONE,
+        },
+    );
+    fold.then(unfold)
+}
+
+pub fn turn_over(
+    mut ev_tween_completed: EventReader<TweenCompleted>,
+    q_flippable: Query<&Flippable>,
+    mut q_visibility: Query<&mut Visibility>,
+) {
+    for completed in ev_tween_completed.iter() {
+        if completed.user_data != FLIP_HALFWAY {
+            continue;
+        }
+        let Ok(flippable) = q_flippable.get(completed.entity) else {
+            continue;
+        };
+        if let Ok(mut visibility) = q_visibility.get_mut(flippable.back) {
+            visibility.is_visible = false;
+        }
+        if let Ok(mut visibility) = q_visibility.get_mut(flippable.front) {
+            visibility.is_visible = true;
+        }
+    }
+}
@@ -24,6 +24,7 @@ WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 use bevy::prelude::*;
 
 pub mod fade;
+pub mod flip;
 
 pub mod effects;
 pub use effects::*;
@@ -0,0 +1,82 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::PLAYERS;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Deserialize, Serialize)]
+pub enum TextScale {
+    #[default]
+    Normal,
+    Large,
+    ExtraLarge,
+}
+
+impl TextScale {
+    pub fn multiplier(&self) -> f32 {
+        match self {
+            TextScale::Normal => 1.0,
+            TextScale::Large => 1.25,
+            TextScale::ExtraLarge => 1.5,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Deserialize, Serialize)]
+pub enum Palette {
+    #[default]
+    Standard,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl Palette {
+    pub fn player_colors(&self) -> [Color; PLAYERS] {
+        match self {
+            Palette::Standard => [Color::rgb(0.85, 0.2, 0.2), Color::rgb(0.2, 0.4, 0.85)],
+            // Okabe-Ito colours, distinguishable with red-green colour blindness
+            Palette::Deuteranopia => [Color::rgb(0.9, 0.62, 0.0), Color::rgb(0.34, 0.71, 0.91)],
+            Palette::Tritanopia => [Color::rgb(0.86, 0.15, 0.15), Color::rgb(0.0, 0.55, 0.6)],
+        }
+    }
+
+    pub fn player_color(&self, seat: usize) -> Color {
+        self.player_colors()[seat % PLAYERS]
+    }
+
+    pub fn dice_face(&self) -> Color {
+        match self {
+            Palette::Standard => Color::rgb(0.95, 0.95, 0.9),
+            Palette::Deuteranopia | Palette::Tritanopia => Color::WHITE,
+        }
+    }
+
+    pub fn dice_pips(&self) -> Color {
+        match self {
+            Palette::Standard => Color::rgb(0.6, 0.1, 0.1),
+            Palette::Deuteranopia | Palette::Tritanopia => Color::BLACK,
+        }
+    }
+}
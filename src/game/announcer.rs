@@ -0,0 +1,180 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::{
+    io,
+    process::{Child, Command},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::Config;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Deserialize, Serialize)]
+pub enum AnnouncerMode {
+    #[default]
+    Off,
+    Log,
+    Stdout,
+    Speech,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AnnouncementKind {
+    Scene,
+    Focus,
+    Game,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Announcement {
+    pub kind: AnnouncementKind,
+    pub text: String,
+}
+
+impl Announcement {
+    pub fn new(kind: AnnouncementKind, text: impl Into<String>) -> Self {
+        Self {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    pub fn scene(text: impl Into<String>) -> Self {
+        Self::new(AnnouncementKind::Scene, text)
+    }
+
+    pub fn focus(text: impl Into<String>) -> Self {
+        Self::new(AnnouncementKind::Focus, text)
+    }
+
+    pub fn game(text: impl Into<String>) -> Self {
+        Self::new(AnnouncementKind::Game, text)
+    }
+}
+
+pub struct Announcer;
+
+impl Plugin for Announcer {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Announcement>()
+            .add_system_to_stage(CoreStage::PostUpdate, announce);
+    }
+}
+
+const SPEECH_COMMAND: &str = "spd-say";
+
+// the spd-say saying the last announcements and the ones that stopped, nothing is waited
+// for so a frame never stalls on a process, they are reaped once done so no zombie
+// processes are left behind
+#[derive(Default)]
+struct Speech {
+    speaking: Option<Child>,
+    cancelling: Option<Child>,
+    stopped: Vec<Child>,
+    next: Option<String>,
+    failed: bool,
+}
+
+impl Speech {
+    // whatever is still being said is cut off, the new announcement is more current
+    fn say(&mut self, text: String) -> io::Result<()> {
+        if let Some(mut child) = self.speaking.take() {
+            if matches!(child.try_wait(), Ok(None)) && self.cancelling.is_none() {
+                // stopping our client doesn't stop the speech server, so it's told to be quiet
+                let cancel = Command::new(SPEECH_COMMAND).arg("--cancel").spawn()?;
+                self.cancelling = Some(cancel);
+            }
+            // it may have finished on its own meanwhile
+            let _ = child.kill();
+            self.stopped.push(child);
+        }
+        self.next = Some(text);
+        self.update()
+    }
+
+    // the next announcement waits for the speech server to be quiet, or it would be
+    // cancelled too
+    fn update(&mut self) -> io::Result<()> {
+        self.stopped
+            .retain_mut(|child| matches!(child.try_wait(), Ok(None)));
+        if let Some(child) = &mut self.speaking {
+            if !matches!(child.try_wait(), Ok(None)) {
+                self.speaking = None;
+            }
+        }
+        if let Some(child) = &mut self.cancelling {
+            if matches!(child.try_wait(), Ok(None)) {
+                return Ok(());
+            }
+            self.cancelling = None;
+        }
+        if let Some(text) = self.next.take() {
+            let child = Command::new(SPEECH_COMMAND)
+                .args(["--wait", "--", &text])
+                .spawn()?;
+            self.speaking = Some(child);
+        }
+        Ok(())
+    }
+}
+
+// everything announced in a frame is said at once, or a burst of game events would
+// only leave the last one to be heard
+fn announce(
+    mut ev_announcement: EventReader<Announcement>,
+    config: Res<Config>,
+    mut speech: Local<Speech>,
+) {
+    let mut said = Vec::new();
+    for announcement in ev_announcement.iter() {
+        match config.announcer {
+            AnnouncerMode::Off => {}
+            AnnouncerMode::Log => info!(
+                "announcement {:?}: {}",
+                announcement.kind, announcement.text
+            ),
+            AnnouncerMode::Stdout => println!("{:?}: {}", announcement.kind, announcement.text),
+            AnnouncerMode::Speech => {
+                if speech.failed {
+                    info!(
+                        "announcement {:?}: {}",
+                        announcement.kind, announcement.text
+                    );
+                } else {
+                    said.push(announcement.text.as_str());
+                }
+            }
+        }
+    }
+    let result = if said.is_empty() {
+        speech.update()
+    } else {
+        speech.say(said.join(". "))
+    };
+    if let Err(err) = result {
+        warn!("can't use {} for announcements: {}", SPEECH_COMMAND, err);
+        speech.failed = true;
+    }
+}
@@ -24,6 +24,9 @@ WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 
+use super::{BattleFile, EventsFile, ItemsFile, MagicFile, MonstersFile, ShopsFile, Theme};
+use crate::scenes::{BoardMap, Menus};
+
 #[derive(AssetCollection, Resource)]
 pub struct Assets {
     #[asset(path = "fonts/FiraSans-Bold.ttf")]
@@ -34,4 +37,24 @@ pub struct Assets {
     pub newolds_sound: Handle<AudioSource>,
     #[asset(path = "menu/click.ogg")]
     pub menu_click: Handle<AudioSource>,
+    #[asset(path = "menu/menus.menu.ron")]
+    pub menus: Handle<Menus>,
+    #[asset(path = "themes/default.theme.ron")]
+    pub default_theme: Handle<Theme>,
+    #[asset(path = "themes/high_contrast.theme.ron")]
+    pub high_contrast_theme: Handle<Theme>,
+    #[asset(path = "boards/classic.board.ron")]
+    pub classic_board: Handle<BoardMap>,
+    #[asset(path = "data/default.magic.ron")]
+    pub magic: Handle<MagicFile>,
+    #[asset(path = "data/default.battle.ron")]
+    pub battle: Handle<BattleFile>,
+    #[asset(path = "data/default.monsters.ron")]
+    pub monsters: Handle<MonstersFile>,
+    #[asset(path = "data/default.items.ron")]
+    pub items: Handle<ItemsFile>,
+    #[asset(path = "data/default.shops.ron")]
+    pub shops: Handle<ShopsFile>,
+    #[asset(path = "data/default.events.ron")]
+    pub events: Handle<EventsFile>,
 }
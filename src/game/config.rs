@@ -25,6 +25,9 @@ WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use super::{AnnouncerMode, LayoutMode, Palette, TextScale, ThemeKind};
+use crate::{ai::Difficulty, rules::MatchRules};
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub enum DisplayMode {
     Windowed,
@@ -48,15 +51,62 @@ impl Not for DisplayMode {
     }
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Deserialize, Serialize)]
+pub enum BoardChoice {
+    #[default]
+    Classic,
+    Random,
+}
+
+// seats in a match, every palette has a colour for each of them
+pub const PLAYERS: usize = 2;
+
+// who plays each seat of a match
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub enum Controller {
+    Human,
+    Computer(Difficulty),
+}
+
+impl Controller {
+    pub fn player_name(&self, seat: usize) -> String {
+        match self {
+            Controller::Human => format!("Player {}", seat + 1),
+            Controller::Computer(_) => format!("Computer {}", seat + 1),
+        }
+    }
+}
+
 #[derive(Resource, Copy, Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Config {
     pub mode: DisplayMode,
+    pub layout: LayoutMode,
+    pub theme: ThemeKind,
+    pub text_scale: TextScale,
+    pub palette: Palette,
+    pub reduced_motion: bool,
+    pub announcer: AnnouncerMode,
+    pub board: BoardChoice,
+    pub rules: MatchRules,
+    pub seats: [Controller; PLAYERS],
+    pub show_odds: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             mode: Default::default(),
+            layout: Default::default(),
+            theme: Default::default(),
+            text_scale: Default::default(),
+            palette: Default::default(),
+            reduced_motion: false,
+            announcer: Default::default(),
+            board: Default::default(),
+            rules: Default::default(),
+            seats: [Controller::Human, Controller::Computer(Difficulty::Normal)],
+            show_odds: false,
         }
     }
 }
@@ -0,0 +1,97 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::marker::PhantomData;
+
+use bevy::{asset::Assets as BevyAssets, ecs::system::SystemParam, prelude::*, reflect::TypeUuid};
+use serde::Deserialize;
+
+use super::Assets;
+use crate::rules::{
+    BattleRules, CardData, DataError, GameData, ItemData, MagicData, MonsterData, ShopData,
+};
+
+#[derive(Debug, Deserialize, TypeUuid)]
+#[serde(transparent)]
+#[uuid = "3c71e0a4-5b2d-4e96-a8f1-7d4b9c2e6a53"]
+pub struct MagicFile(pub MagicData);
+
+#[derive(Debug, Deserialize, TypeUuid)]
+#[serde(transparent)]
+#[uuid = "e4a19d3b-2c8f-4b71-96d5-0f3a7b8c1e24"]
+pub struct BattleFile(pub BattleRules);
+
+#[derive(Debug, Deserialize, TypeUuid)]
+#[serde(transparent)]
+#[uuid = "7b2e5f90-1d4a-4c38-a6e7-9f0c3d8b2a61"]
+pub struct MonstersFile(pub MonsterData);
+
+#[derive(Debug, Deserialize, TypeUuid)]
+#[serde(transparent)]
+#[uuid = "c58d3e17-9a6b-4f02-b4c1-2e7f8a5d0b93"]
+pub struct ItemsFile(pub ItemData);
+
+#[derive(Debug, Deserialize, TypeUuid)]
+#[serde(transparent)]
+#[uuid = "2f9a6c84-e01b-4d57-8c3e-6b1d7a4f9e02"]
+pub struct ShopsFile(pub ShopData);
+
+#[derive(Debug, Deserialize, TypeUuid)]
+#[serde(transparent)]
+#[uuid = "a6d2f3c9-48e1-4b7a-9e05-3c8b1f6d2e77"]
+pub struct EventsFile(pub CardData);
+
+// every loaded data file, to put together the rules data
+#[derive(SystemParam)]
+pub struct DataFiles<'w, 's> {
+    assets: Res<'w, Assets>,
+    magic: Res<'w, BevyAssets<MagicFile>>,
+    battle: Res<'w, BevyAssets<BattleFile>>,
+    monsters: Res<'w, BevyAssets<MonstersFile>>,
+    items: Res<'w, BevyAssets<ItemsFile>>,
+    shops: Res<'w, BevyAssets<ShopsFile>>,
+    events: Res<'w, BevyAssets<EventsFile>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's> DataFiles<'w, 's> {
+    pub fn game_data(&self) -> Result<GameData, DataError> {
+        let data = GameData {
+            magic: loaded(&self.magic, &self.assets.magic).0.clone(),
+            battle: loaded(&self.battle, &self.assets.battle).0.clone(),
+            monsters: loaded(&self.monsters, &self.assets.monsters).0.clone(),
+            items: loaded(&self.items, &self.assets.items).0.clone(),
+            shops: loaded(&self.shops, &self.assets.shops).0.clone(),
+            cards: loaded(&self.events, &self.assets.events).0.clone(),
+        };
+        data.validate()?;
+        Ok(data)
+    }
+}
+
+fn loaded<'a, T: bevy::asset::Asset>(assets: &'a BevyAssets<T>, handle: &Handle<T>) -> &'a T {
+    assets
+        .get(handle)
+        .expect("data should be loaded before the game starts")
+}
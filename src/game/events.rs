@@ -23,7 +23,11 @@ WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
 use bevy::prelude::*;
 
-use super::{Config, DisplayMode};
+use super::{
+    AnnouncerMode, BoardChoice, Config, Controller, DisplayMode, LayoutMode, Palette, TextScale,
+    ThemeKind,
+};
+use crate::rules::Victory;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct ChangeDisplayMode {
@@ -34,26 +38,106 @@ impl ChangeDisplayMode {
     pub fn to(mode: DisplayMode) -> Self {
         Self { mode }
     }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ChangeLayout {
+    pub layout: LayoutMode,
+}
+
+impl ChangeLayout {
+    pub fn to(layout: LayoutMode) -> Self {
+        Self { layout }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ChangeBoard {
+    pub board: BoardChoice,
+}
 
-    pub fn windowed() -> Self {
-        Self::to(DisplayMode::Windowed)
+impl ChangeBoard {
+    pub fn to(board: BoardChoice) -> Self {
+        Self { board }
     }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ChangeSeat {
+    pub seat: usize,
+    pub controller: Controller,
+}
+
+impl ChangeSeat {
+    pub fn to(seat: usize, controller: Controller) -> Self {
+        Self { seat, controller }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ChangeOdds {
+    pub show: bool,
+}
+
+impl ChangeOdds {
+    pub fn to(show: bool) -> Self {
+        Self { show }
+    }
+}
 
-    pub fn full_screen() -> Self {
-        Self::to(DisplayMode::FullScreen)
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ChangeTheme {
+    pub theme: ThemeKind,
+}
+
+impl ChangeTheme {
+    pub fn to(theme: ThemeKind) -> Self {
+        Self { theme }
     }
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum ChangeAccessibility {
+    TextScale(TextScale),
+    Palette(Palette),
+    ReducedMotion(bool),
+    Announcer(AnnouncerMode),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum ChangeMatchRules {
+    Victory(Victory),
+    GoldTarget(u32),
+    TurnLimit(u32),
+    Lives(u32),
+}
+
 pub struct Handler;
 
 impl Plugin for Handler {
     fn build(&self, app: &mut App) {
-        app.add_event::<ChangeDisplayMode>().add_system(game_events);
+        app.add_event::<ChangeDisplayMode>()
+            .add_event::<ChangeLayout>()
+            .add_event::<ChangeTheme>()
+            .add_event::<ChangeBoard>()
+            .add_event::<ChangeAccessibility>()
+            .add_event::<ChangeMatchRules>()
+            .add_event::<ChangeSeat>()
+            .add_event::<ChangeOdds>()
+            .add_system(game_events);
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn game_events(
     mut ev_change_display_mode: EventReader<ChangeDisplayMode>,
+    mut ev_change_layout: EventReader<ChangeLayout>,
+    mut ev_change_theme: EventReader<ChangeTheme>,
+    mut ev_change_accessibility: EventReader<ChangeAccessibility>,
+    mut ev_change_board: EventReader<ChangeBoard>,
+    mut ev_change_match_rules: EventReader<ChangeMatchRules>,
+    mut ev_change_seat: EventReader<ChangeSeat>,
+    mut ev_change_odds: EventReader<ChangeOdds>,
     mut config: ResMut<Config>,
     mut windows: ResMut<Windows>,
 ) {
@@ -67,4 +151,44 @@ fn game_events(
             DisplayMode::FullScreen => WindowMode::BorderlessFullscreen,
         });
     }
+
+    for change_layout in ev_change_layout.iter() {
+        config.layout = change_layout.layout;
+    }
+
+    for change_theme in ev_change_theme.iter() {
+        config.theme = change_theme.theme;
+    }
+
+    for change_board in ev_change_board.iter() {
+        config.board = change_board.board;
+    }
+
+    for change_accessibility in ev_change_accessibility.iter() {
+        match change_accessibility {
+            ChangeAccessibility::TextScale(text_scale) => config.text_scale = *text_scale,
+            ChangeAccessibility::Palette(palette) => config.palette = *palette,
+            ChangeAccessibility::ReducedMotion(reduced) => config.reduced_motion = *reduced,
+            ChangeAccessibility::Announcer(announcer) => config.announcer = *announcer,
+        }
+    }
+
+    for change_match_rules in ev_change_match_rules.iter() {
+        match change_match_rules {
+            ChangeMatchRules::Victory(victory) => config.rules.victory = *victory,
+            ChangeMatchRules::GoldTarget(gold) => config.rules.gold_target = *gold,
+            ChangeMatchRules::TurnLimit(turns) => config.rules.turn_limit = *turns,
+            ChangeMatchRules::Lives(lives) => config.rules.lives = *lives,
+        }
+    }
+
+    for change_seat in ev_change_seat.iter() {
+        if let Some(seat) = config.seats.get_mut(change_seat.seat) {
+            *seat = change_seat.controller;
+        }
+    }
+
+    for change_odds in ev_change_odds.iter() {
+        config.show_odds = change_odds.show;
+    }
 }
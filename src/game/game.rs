@@ -21,30 +21,45 @@ OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 ***/
 
+use std::path::{Path, PathBuf};
+
 use bevy::{
-    app::AppExit,
-    log::LogPlugin,
-    prelude::*,
-    render::camera::ScalingMode,
-    text::TextSettings,
-    window::{WindowResizeConstraints, WindowResized},
+    app::AppExit, log::LogPlugin, prelude::*, render::camera::ScalingMode, text::TextSettings,
+    window::WindowResizeConstraints,
 };
 
 use bevy_asset_loader::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
 use bevy_pkv::PkvStore;
 use bevy_tweening::TweeningPlugin;
 use iyes_progress::ProgressPlugin;
 
-use super::{events, Assets, Config, DisplayMode, State};
-use crate::{effects, scenes};
+use super::{
+    events, layout, theme, Announcer, Assets, BattleFile, Config, DisplayMode, EventsFile,
+    ItemsFile, Layout, MagicFile, MonstersFile, ShopsFile, State, Theme, DESIGN_RESOLUTION,
+};
+use crate::{effects, rng::GameRng, scenes};
 
 const TITLE: &str = "Dice Master!";
 const LOG_FILTER: &str = "wgpu=error,dice_master=debug";
-const DESIGN_RESOLUTION: Vec2 = Vec2::new(1920., 1080.);
 const CLEAR_COLOR: Color = Color::rgb(0., 0., 0.);
 const COMPANY: &str = "NewOlds";
 const APP_NAME: &str = "dice_master";
 const CONFIG_KEY: &str = "game_config";
+pub const SAVE_KEY: &str = "game_save";
+
+// the per user folder bevy_pkv keeps the config in, replays and logs go along with it
+pub fn data_dir() -> PathBuf {
+    directories::ProjectDirs::from("", COMPANY, APP_NAME).map_or_else(
+        || Path::new(".").to_path_buf(),
+        |dirs| dirs.data_dir().to_path_buf(),
+    )
+}
+
+// the last match played, kept to watch again from the main menu
+pub fn last_replay() -> PathBuf {
+    data_dir().join("replays").join("last.replay.ron")
+}
 
 pub struct Game {
     app: App,
@@ -86,7 +101,9 @@ impl Game {
             )
             .add_state(State::Loading)
             .add_plugin(scenes::Loading)
-            .add_plugin(scenes::Hello)
+            .add_plugin(scenes::BoardScene)
+            .add_plugin(scenes::BattleScene)
+            .add_plugin(scenes::Results)
             .add_plugin(scenes::Menu)
             .add_plugin(scenes::Splash);
     }
@@ -99,13 +116,20 @@ impl Game {
                 ..default()
             })
             .insert_resource(config)
+            .init_resource::<Theme>()
+            .init_resource::<Layout>()
+            .insert_resource(GameRng::from_entropy())
             .insert_resource(store);
     }
 
     fn add_main_systems(&mut self) {
         self.app
             .add_startup_system(setup)
-            .add_system(scale_ui)
+            .add_startup_system(layout::setup_letterbox)
+            .add_system(layout::scale_ui)
+            .add_system(layout::place_safe_areas.after(layout::scale_ui))
+            .add_system(layout::place_letterbox.after(layout::scale_ui))
+            .add_system(theme::apply_theme)
             .add_system(toggle_full_screen_on_alt_enter)
             .add_system(bevy::window::close_on_esc)
             .add_system_to_stage(CoreStage::PostUpdate, app_exit);
@@ -157,26 +181,27 @@ impl Game {
         self.app
             .add_plugin(events::Handler)
             .add_plugin(effects::Handler)
+            .add_plugin(Announcer)
+            .add_plugin(RonAssetPlugin::<Theme>::new(&["theme.ron"]))
+            .add_plugin(RonAssetPlugin::<MagicFile>::new(&["magic.ron"]))
+            .add_plugin(RonAssetPlugin::<BattleFile>::new(&["battle.ron"]))
+            .add_plugin(RonAssetPlugin::<MonstersFile>::new(&["monsters.ron"]))
+            .add_plugin(RonAssetPlugin::<ItemsFile>::new(&["items.ron"]))
+            .add_plugin(RonAssetPlugin::<ShopsFile>::new(&["shops.ron"]))
+            .add_plugin(RonAssetPlugin::<EventsFile>::new(&["events.ron"]))
             .add_plugin(TweeningPlugin);
     }
 }
 
 fn setup(mut commands: Commands) {
     let mut camera_bundle = Camera2dBundle::default();
-    camera_bundle.projection.scaling_mode = ScalingMode::FixedHorizontal(DESIGN_RESOLUTION.x);
+    camera_bundle.projection.scaling_mode = ScalingMode::Auto {
+        min_width: DESIGN_RESOLUTION.x,
+        min_height: DESIGN_RESOLUTION.y,
+    };
     commands.spawn(camera_bundle);
 }
 
-fn scale_ui(resize_event: Res<Events<WindowResized>>, mut ui_scale: ResMut<UiScale>) {
-    let mut reader = resize_event.get_reader();
-    for e in reader.iter(&resize_event) {
-        let scale_x = e.width / DESIGN_RESOLUTION.x;
-        let scale_y = e.height / DESIGN_RESOLUTION.y;
-        let scale = (scale_x * scale_y).sqrt();
-        ui_scale.scale = scale as f64;
-    }
-}
-
 fn toggle_full_screen_on_alt_enter(
     input: Res<Input<KeyCode>>,
     config: Res<Config>,
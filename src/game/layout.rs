@@ -0,0 +1,265 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::{prelude::*, window::WindowResized};
+use serde::{Deserialize, Serialize};
+
+use super::Config;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Deserialize, Serialize)]
+pub enum LayoutMode {
+    #[default]
+    Expand,
+    Letterbox,
+    SafeArea,
+}
+
+pub const DESIGN_RESOLUTION: Vec2 = Vec2::new(1920., 1080.);
+const SAFE_AREA_MARGIN: f32 = 0.05;
+const BARS_Z_INDEX: i32 = 1000;
+
+#[derive(Resource, Debug, Copy, Clone, PartialEq)]
+pub struct Layout {
+    pub ui_scale: f64,
+    pub window: Rect,
+    pub design_area: Rect,
+    pub safe_area: Rect,
+    pub letterbox: bool,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::compute(DESIGN_RESOLUTION, DESIGN_RESOLUTION, LayoutMode::default())
+    }
+}
+
+impl Layout {
+    // everything is in logical pixels with the origin on the top left of the window,
+    // the design area is the biggest rectangle with the design aspect ratio that fits
+    pub fn compute(window: Vec2, design: Vec2, mode: LayoutMode) -> Self {
+        let scale = (window.x / design.x).min(window.y / design.y);
+        let window_rect = Rect::from_corners(Vec2::ZERO, window);
+        let design_area = Rect::from_center_size(window / 2., design * scale);
+        let safe_area = match mode {
+            LayoutMode::Expand => window_rect,
+            LayoutMode::Letterbox => design_area,
+            LayoutMode::SafeArea => {
+                let margin = design_area.size() * SAFE_AREA_MARGIN;
+                Rect::from_corners(design_area.min + margin, design_area.max - margin)
+            }
+        };
+        Self {
+            ui_scale: scale as f64,
+            window: window_rect,
+            design_area,
+            safe_area,
+            letterbox: mode == LayoutMode::Letterbox,
+        }
+    }
+
+    pub fn bars(&self) -> [Rect; 4] {
+        let Self {
+            window,
+            design_area,
+            ..
+        } = *self;
+        [
+            Rect::from_corners(window.min, Vec2::new(window.max.x, design_area.min.y)),
+            Rect::from_corners(Vec2::new(window.min.x, design_area.max.y), window.max),
+            Rect::from_corners(
+                Vec2::new(window.min.x, design_area.min.y),
+                Vec2::new(design_area.min.x, design_area.max.y),
+            ),
+            Rect::from_corners(
+                Vec2::new(design_area.max.x, design_area.min.y),
+                Vec2::new(window.max.x, design_area.max.y),
+            ),
+        ]
+    }
+}
+
+#[derive(Component)]
+pub struct SafeArea;
+
+#[derive(Component)]
+pub struct LetterboxBar(usize);
+
+pub fn setup_letterbox(mut commands: Commands) {
+    for bar in 0..4 {
+        commands.spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::BLACK.into(),
+                visibility: Visibility::INVISIBLE,
+                z_index: ZIndex::Global(BARS_Z_INDEX),
+                ..default()
+            },
+            LetterboxBar(bar),
+        ));
+    }
+}
+
+pub fn scale_ui(
+    mut resize_event: EventReader<WindowResized>,
+    windows: Res<Windows>,
+    config: Res<Config>,
+    mut layout: ResMut<Layout>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    if resize_event.iter().last().is_none() && !config.is_changed() {
+        return;
+    }
+    if let Some(window) = windows.get_primary() {
+        let size = Vec2::new(window.width(), window.height());
+        // minimized windows report a zero size
+        if size.min_element() <= 0. {
+            return;
+        }
+        let new_layout = Layout::compute(size, DESIGN_RESOLUTION, config.layout);
+        if *layout != new_layout {
+            *layout = new_layout;
+            ui_scale.scale = layout.ui_scale;
+        }
+    }
+}
+
+pub fn place_safe_areas(
+    layout: Res<Layout>,
+    mut q_safe_area: Query<&mut Style, With<SafeArea>>,
+    q_added: Query<(), Added<SafeArea>>,
+) {
+    if !layout.is_changed() && q_added.is_empty() {
+        return;
+    }
+    let scale = layout.ui_scale as f32;
+    for mut style in q_safe_area.iter_mut() {
+        place(&mut style, layout.safe_area, scale);
+    }
+}
+
+pub fn place_letterbox(
+    layout: Res<Layout>,
+    mut q_bars: Query<(&LetterboxBar, &mut Style, &mut Visibility)>,
+) {
+    if !layout.is_changed() {
+        return;
+    }
+    let bars = layout.bars();
+    let scale = layout.ui_scale as f32;
+    for (bar, mut style, mut visibility) in q_bars.iter_mut() {
+        place(&mut style, bars[bar.0], scale);
+        visibility.is_visible = layout.letterbox;
+    }
+}
+
+// ui values are multiplied by the UiScale, so we undo it to place in window pixels
+fn place(style: &mut Style, area: Rect, scale: f32) {
+    style.position_type = PositionType::Absolute;
+    style.position = UiRect {
+        left: Val::Px(area.min.x / scale),
+        top: Val::Px(area.min.y / scale),
+        ..default()
+    };
+    style.size = Size::new(
+        Val::Px(area.width() / scale),
+        Val::Px(area.height() / scale),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // left, top, right and bottom distances from the window edges to the safe area
+    fn insets(layout: &Layout) -> [f32; 4] {
+        let Layout {
+            window, safe_area, ..
+        } = layout;
+        [
+            safe_area.min.x - window.min.x,
+            safe_area.min.y - window.min.y,
+            window.max.x - safe_area.max.x,
+            window.max.y - safe_area.max.y,
+        ]
+    }
+
+    fn assert_layout(window: (f32, f32), mode: LayoutMode, scale: f64, expected: [f32; 4]) {
+        let layout = Layout::compute(Vec2::new(window.0, window.1), DESIGN_RESOLUTION, mode);
+        assert!(
+            (layout.ui_scale - scale).abs() < 1e-5,
+            "{:?} {:?} scale {} expected {}",
+            window,
+            mode,
+            layout.ui_scale,
+            scale
+        );
+        let insets = insets(&layout);
+        for (inset, expected) in insets.iter().zip(expected) {
+            assert!(
+                (inset - expected).abs() < 1e-2,
+                "{:?} {:?} insets {:?} expected {:?}",
+                window,
+                mode,
+                insets,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn common_resolutions() {
+        #[rustfmt::skip]
+        let table = [
+            ((1280., 720.), 2. / 3., [0., 0., 0., 0.], [64., 36., 64., 36.]),
+            ((1920., 1080.), 1., [0., 0., 0., 0.], [96., 54., 96., 54.]),
+            ((2560., 1440.), 4. / 3., [0., 0., 0., 0.], [128., 72., 128., 72.]),
+            ((2560., 1080.), 1., [320., 0., 320., 0.], [416., 54., 416., 54.]),
+            ((1080., 1920.), 0.5625, [0., 656.25, 0., 656.25], [54., 686.625, 54., 686.625]),
+        ];
+        for (window, scale, letterbox, safe_area) in table {
+            assert_layout(window, LayoutMode::Expand, scale, [0., 0., 0., 0.]);
+            assert_layout(window, LayoutMode::Letterbox, scale, letterbox);
+            assert_layout(window, LayoutMode::SafeArea, scale, safe_area);
+        }
+    }
+
+    #[test]
+    fn only_letterbox_shows_the_bars() {
+        let window = Vec2::new(2560., 1080.);
+        for mode in [
+            LayoutMode::Expand,
+            LayoutMode::Letterbox,
+            LayoutMode::SafeArea,
+        ] {
+            let layout = Layout::compute(window, DESIGN_RESOLUTION, mode);
+            assert_eq!(layout.letterbox, mode == LayoutMode::Letterbox);
+            let [top, bottom, left, right] = layout.bars();
+            assert_eq!(top.height() + bottom.height(), 0.);
+            assert_eq!(left.width(), 320.);
+            assert_eq!(right.width(), 320.);
+        }
+    }
+}
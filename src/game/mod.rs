@@ -28,10 +28,28 @@ mod state;
 pub use state::State;
 
 mod game;
-pub use game::Game;
+pub use game::{data_dir, last_replay, Game, SAVE_KEY};
 
 pub mod events;
 
 mod config;
+pub use config::BoardChoice;
 pub use config::Config;
+pub use config::Controller;
 pub use config::DisplayMode;
+pub use config::PLAYERS;
+
+mod data;
+pub use data::{BattleFile, DataFiles, EventsFile, ItemsFile, MagicFile, MonstersFile, ShopsFile};
+
+mod accessibility;
+pub use accessibility::{Palette, TextScale};
+
+mod announcer;
+pub use announcer::{Announcement, Announcer, AnnouncerMode};
+
+pub mod layout;
+pub use layout::{Layout, LayoutMode, SafeArea, DESIGN_RESOLUTION};
+
+mod theme;
+pub use theme::{Theme, ThemeKind};
@@ -26,5 +26,7 @@ pub enum State {
     Loading,
     Splash,
     Menu,
-    Hello,
+    Board,
+    Battle,
+    Results,
 }
@@ -0,0 +1,109 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::{prelude::*, reflect::TypeUuid};
+use serde::{Deserialize, Serialize};
+
+use super::{Assets, Config, TextScale};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Deserialize, Serialize)]
+pub enum ThemeKind {
+    #[default]
+    Default,
+    HighContrast,
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Deserialize, TypeUuid)]
+#[uuid = "0b6f7c2e-8a52-4d8e-b1a3-6c2f4e9d7a15"]
+pub struct Theme {
+    pub panel_color: Color,
+    pub title_color: Color,
+    pub text_color: Color,
+    pub normal_color: Color,
+    pub hovered_color: Color,
+    pub hovered_selected_color: Color,
+    pub clicked_color: Color,
+    pub title_font_size: f32,
+    pub label_font_size: f32,
+    pub button_font_size: f32,
+    pub button_font_size_small: f32,
+}
+
+impl Theme {
+    pub fn scaled(&self, text_scale: TextScale) -> Self {
+        let multiplier = text_scale.multiplier();
+        Self {
+            title_font_size: self.title_font_size * multiplier,
+            label_font_size: self.label_font_size * multiplier,
+            button_font_size: self.button_font_size * multiplier,
+            button_font_size_small: self.button_font_size_small * multiplier,
+            ..self.clone()
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            panel_color: Color::GRAY,
+            title_color: Color::WHITE,
+            text_color: Color::rgb(0.9, 0.9, 0.9),
+            normal_color: Color::rgb(0.15, 0.15, 0.15),
+            hovered_color: Color::rgb(0.25, 0.25, 0.25),
+            hovered_selected_color: Color::rgb(0.25, 0.65, 0.25),
+            clicked_color: Color::rgb(0.35, 0.75, 0.35),
+            title_font_size: 80.0,
+            label_font_size: 45.0,
+            button_font_size: 40.0,
+            button_font_size_small: 30.0,
+        }
+    }
+}
+
+use bevy::asset::Assets as BevyAssets;
+pub fn apply_theme(
+    mut theme: ResMut<Theme>,
+    config: Res<Config>,
+    assets: Option<Res<Assets>>,
+    themes: Res<BevyAssets<Theme>>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+    if !config.is_changed() && !assets.is_added() {
+        return;
+    }
+
+    let handle = match config.theme {
+        ThemeKind::Default => &assets.default_theme,
+        ThemeKind::HighContrast => &assets.high_contrast_theme,
+    };
+    // only write when it differs so config changes elsewhere don't restyle every screen
+    let Some(loaded) = themes.get(handle) else {
+        return;
+    };
+    let scaled = loaded.scaled(config.text_scale);
+    if *theme != scaled {
+        *theme = scaled;
+    }
+}
@@ -28,6 +28,7 @@ mod effects;
 mod game;
 mod scenes;
 
+use dice_master::{ai, board, dice, rng, rules};
 use game::Game;
 
 fn main() {
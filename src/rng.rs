@@ -0,0 +1,78 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use rand::{Error, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+// all the randomness of a match comes from here so it can be reproduced from the seed
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
+pub struct GameRng {
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn from_entropy() -> Self {
+        Self::from_seed(rand::random())
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // how many numbers were drawn since it was seeded
+    pub fn position(&self) -> u128 {
+        self.rng.get_word_pos()
+    }
+
+    // an independent generator, so a task can run without borrowing this one
+    pub fn fork(&mut self) -> Self {
+        Self::from_seed(self.next_u64())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
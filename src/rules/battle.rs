@@ -0,0 +1,305 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::Stats;
+use crate::dice::{DicePool, Distribution, Roll};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Fighter {
+    Player(usize),
+    Monster(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BattleRules {
+    pub health: u32,
+    pub attack: DicePool,
+    pub defense: DicePool,
+    // what running away from a battle costs the attacker
+    #[serde(default = "default_flee_damage")]
+    pub flee_damage: u32,
+}
+
+fn default_flee_damage() -> u32 {
+    2
+}
+
+impl BattleRules {
+    pub fn starting_stats(&self) -> Stats {
+        Stats {
+            max_health: self.health,
+            attack: self.attack,
+            defense: self.defense,
+        }
+    }
+}
+
+impl Default for BattleRules {
+    fn default() -> Self {
+        Self {
+            health: 10,
+            attack: DicePool::new(2, 6),
+            defense: DicePool::new(1, 6),
+            flee_damage: default_flee_damage(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct Modifier {
+    pub source: String,
+    pub attack: i32,
+    pub defense: i32,
+    #[serde(default)]
+    pub attack_dice: u8,
+    #[serde(default)]
+    pub defense_dice: u8,
+}
+
+// a snapshot of a fighter as the battle starts
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Combatant {
+    pub fighter: Fighter,
+    pub name: String,
+    pub health: u32,
+    pub max_health: u32,
+    pub attack: DicePool,
+    pub defense: DicePool,
+    pub modifiers: Vec<Modifier>,
+    pub first_strike: bool,
+}
+
+impl Combatant {
+    pub fn attack_pool(&self) -> DicePool {
+        let mut pool = self.attack;
+        for modifier in &self.modifiers {
+            pool.dice += modifier.attack_dice;
+            pool.bonus += modifier.attack;
+        }
+        pool
+    }
+
+    pub fn defense_pool(&self) -> DicePool {
+        let mut pool = self.defense;
+        for modifier in &self.modifiers {
+            pool.dice += modifier.defense_dice;
+            pool.bonus += modifier.defense;
+        }
+        pool
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Exchange {
+    pub striker: Fighter,
+    pub target: Fighter,
+    pub attack: Roll,
+    pub defense: Roll,
+    pub damage: u32,
+    pub remaining: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BattleReport {
+    pub attacker: Combatant,
+    pub defender: Combatant,
+    pub exchanges: Vec<Exchange>,
+    pub defeated: Option<Fighter>,
+}
+
+impl BattleReport {
+    pub fn combatant(&self, fighter: Fighter) -> &Combatant {
+        if self.attacker.fighter == fighter {
+            &self.attacker
+        } else {
+            &self.defender
+        }
+    }
+}
+
+// the attacker strikes first unless only the defender has first strike
+fn strike_order(attacker: &Combatant, defender: &Combatant) -> [(usize, usize); 2] {
+    if defender.first_strike && !attacker.first_strike {
+        [(1, 0), (0, 1)]
+    } else {
+        [(0, 1), (1, 0)]
+    }
+}
+
+// if the one struck first is still standing it strikes back
+pub fn resolve(attacker: Combatant, defender: Combatant, rng: &mut impl Rng) -> BattleReport {
+    let mut health = [attacker.health, defender.health];
+    let mut exchanges = Vec::new();
+    let mut defeated = None;
+    for (striker, target) in strike_order(&attacker, &defender) {
+        let combatants = [&attacker, &defender];
+        let attack = combatants[striker].attack_pool().roll(rng);
+        let defense = combatants[target].defense_pool().roll(rng);
+        let damage = (attack.total() - defense.total()).max(0) as u32;
+        health[target] = health[target].saturating_sub(damage);
+        exchanges.push(Exchange {
+            striker: combatants[striker].fighter,
+            target: combatants[target].fighter,
+            attack,
+            defense,
+            damage,
+            remaining: health[target],
+        });
+        if health[target] == 0 {
+            defeated = Some(combatants[target].fighter);
+            break;
+        }
+    }
+    BattleReport {
+        attacker,
+        defender,
+        exchanges,
+        defeated,
+    }
+}
+
+// the exact chances of how a battle ends, before any die is rolled
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BattleOdds {
+    pub attacker_wins: f64,
+    pub defender_wins: f64,
+    pub both_stand: f64,
+}
+
+// what a strike takes off the target, the defence can stop it but never heals
+pub fn damage(striker: &Combatant, target: &Combatant) -> Distribution {
+    striker
+        .attack_pool()
+        .distribution()
+        .minus(&target.defense_pool().distribution())
+        .floored(0)
+}
+
+// works the two strikes out the same way resolve does, the second only comes when the
+// first one did not defeat its target
+pub fn odds(attacker: &Combatant, defender: &Combatant) -> BattleOdds {
+    let combatants = [attacker, defender];
+    let mut wins = [0.; 2];
+    let mut standing = 1.;
+    for (striker, target) in strike_order(attacker, defender) {
+        let defeats = damage(combatants[striker], combatants[target])
+            .at_least(combatants[target].health as i32);
+        wins[striker] += standing * defeats;
+        standing *= 1. - defeats;
+    }
+    BattleOdds {
+        attacker_wins: wins[0],
+        defender_wins: wins[1],
+        both_stand: standing,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn combatant(seat: usize, health: u32, attack: DicePool, defense: DicePool) -> Combatant {
+        Combatant {
+            fighter: Fighter::Player(seat),
+            name: format!("Seat {}", seat),
+            health,
+            max_health: health,
+            attack,
+            defense,
+            modifiers: Vec::new(),
+            first_strike: false,
+        }
+    }
+
+    // every total of the pool, once for each way the dice can fall
+    fn totals(pool: DicePool) -> Vec<i32> {
+        let mut totals = vec![pool.bonus];
+        for _ in 0..pool.dice {
+            totals = totals
+                .iter()
+                .flat_map(|total| (1..=pool.sides as i32).map(move |face| total + face))
+                .collect();
+        }
+        totals
+    }
+
+    // plays every way the two strikes can fall the way resolve does
+    fn brute_force(attacker: &Combatant, defender: &Combatant) -> [f64; 3] {
+        let combatants = [attacker, defender];
+        let [(first, first_target), (second, second_target)] = strike_order(attacker, defender);
+        let strike = |striker: usize, target: usize| {
+            let attack = totals(combatants[striker].attack_pool());
+            let defense = totals(combatants[target].defense_pool());
+            let chance = 1. / (attack.len() * defense.len()) as f64;
+            let defeats = attack
+                .iter()
+                .flat_map(|attack| defense.iter().map(move |defense| attack - defense))
+                .filter(|damage| *damage >= combatants[target].health as i32)
+                .count();
+            defeats as f64 * chance
+        };
+        let mut wins = [0.; 2];
+        let first_defeats = strike(first, first_target);
+        wins[first] = first_defeats;
+        let second_defeats = strike(second, second_target);
+        wins[second] = (1. - first_defeats) * second_defeats;
+        [
+            wins[0],
+            wins[1],
+            (1. - first_defeats) * (1. - second_defeats),
+        ]
+    }
+
+    #[test]
+    fn odds_match_every_roll() {
+        let mut attacker = combatant(0, 3, DicePool::new(2, 4), DicePool::new(1, 3));
+        let mut defender = combatant(1, 2, DicePool::new(1, 6), DicePool::new(1, 2));
+        defender.attack.bonus = 1;
+        attacker.modifiers.push(Modifier {
+            source: "Sword".into(),
+            attack: 1,
+            defense: -1,
+            attack_dice: 0,
+            defense_dice: 1,
+        });
+        for first_strike in [false, true] {
+            defender.first_strike = first_strike;
+            let odds = odds(&attacker, &defender);
+            let expected = brute_force(&attacker, &defender);
+            let found = [odds.attacker_wins, odds.defender_wins, odds.both_stand];
+            for (found, expected) in found.iter().zip(expected) {
+                assert!((found - expected).abs() < EPSILON, "{:?}", odds);
+            }
+            assert!(
+                (found.iter().sum::<f64>() - 1.).abs() < EPSILON,
+                "{:?}",
+                odds
+            );
+        }
+    }
+}
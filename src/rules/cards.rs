@@ -0,0 +1,132 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::TileKind;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum CardKind {
+    Blessing,
+    Curse,
+    Trap,
+    Treasure,
+    Weather,
+}
+
+impl CardKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CardKind::Blessing => "Blessing",
+            CardKind::Curse => "Curse",
+            CardKind::Trap => "Trap",
+            CardKind::Treasure => "Treasure",
+            CardKind::Weather => "Weather",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default, Deserialize, Serialize)]
+pub enum CardTarget {
+    #[default]
+    Drawer,
+    Others,
+    Everyone,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum CardEffect {
+    Heal(u32),
+    Damage(u32),
+    Gold(i32),
+    Item(String),
+    // a random rune when there is no id
+    Rune(Option<String>),
+    Teleport(TileKind),
+    Hinder(i32),
+    SpawnMonster,
+    Restock,
+    Weather {
+        name: String,
+        rounds: u32,
+        movement: i32,
+    },
+}
+
+impl CardEffect {
+    // effects on the board ignore the target
+    pub fn is_board_effect(&self) -> bool {
+        matches!(
+            self,
+            CardEffect::SpawnMonster | CardEffect::Restock | CardEffect::Weather { .. }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CardStep {
+    #[serde(default)]
+    pub target: CardTarget,
+    pub effect: CardEffect,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CardDef {
+    pub id: String,
+    pub name: String,
+    pub kind: CardKind,
+    pub text: String,
+    pub effects: Vec<CardStep>,
+    #[serde(default = "default_copies")]
+    pub copies: u32,
+}
+
+fn default_copies() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct CardData {
+    pub cards: Vec<CardDef>,
+}
+
+impl CardData {
+    pub fn card(&self, id: &str) -> Option<&CardDef> {
+        self.cards.iter().find(|card| card.id == id)
+    }
+
+    // one entry for each copy of each card, ready to be shuffled
+    pub fn deck(&self) -> Vec<String> {
+        self.cards
+            .iter()
+            .flat_map(|card| (0..card.copies).map(|_| card.id.clone()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Weather {
+    pub name: String,
+    pub rounds: u32,
+    pub movement: i32,
+}
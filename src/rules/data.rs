@@ -0,0 +1,143 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::{fmt, fs, io, path::Path};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{BattleRules, CardData, CardEffect, Goods, ItemData, MagicData, MonsterData, ShopData};
+
+#[derive(Debug)]
+pub enum DataError {
+    Io(String, io::Error),
+    Parse(String, ron::error::SpannedError),
+    Invalid(String, String),
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataError::Io(file, err) => write!(f, "can't read {}: {}", file, err),
+            DataError::Parse(file, err) => write!(f, "can't parse {}: {}", file, err),
+            DataError::Invalid(file, err) => write!(f, "invalid {}: {}", file, err),
+        }
+    }
+}
+
+impl std::error::Error for DataError {}
+
+// every definition the rules need, each part comes from its own file so designers can
+// tune one without touching the others
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct GameData {
+    pub magic: MagicData,
+    pub battle: BattleRules,
+    pub monsters: MonsterData,
+    pub items: ItemData,
+    pub shops: ShopData,
+    pub cards: CardData,
+}
+
+impl GameData {
+    pub fn validate(&self) -> Result<(), DataError> {
+        self.magic
+            .validate()
+            .map_err(|err| DataError::Invalid("magic".into(), err))?;
+        self.monsters
+            .validate()
+            .map_err(|err| DataError::Invalid("monsters".into(), err))?;
+        self.items
+            .validate()
+            .map_err(|err| DataError::Invalid("items".into(), err))?;
+        self.shops
+            .validate(&self.items, &self.magic)
+            .map_err(|err| DataError::Invalid("shops".into(), err))?;
+        for card in &self.cards.cards {
+            for step in &card.effects {
+                let known = match &step.effect {
+                    CardEffect::Item(item) => self.items.item(item).is_some(),
+                    CardEffect::Rune(Some(rune)) => self.magic.rune(rune).is_some(),
+                    _ => true,
+                };
+                if !known {
+                    return Err(DataError::Invalid(
+                        "events".into(),
+                        format!("card {} gives unknown {:?}", card.id, step.effect),
+                    ));
+                }
+            }
+        }
+        for monster in &self.monsters.monsters {
+            if let Some(entry) = monster
+                .loot
+                .iter()
+                .find(|entry| self.items.item(&entry.item).is_none())
+            {
+                return Err(DataError::Invalid(
+                    "monsters".into(),
+                    format!("monster {} drops unknown item {}", monster.id, entry.item),
+                ));
+            }
+        }
+        if self.battle.health == 0 {
+            return Err(DataError::Invalid(
+                "battle".into(),
+                "players need some health".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn goods_name(&self, goods: &Goods) -> String {
+        match goods {
+            Goods::Item(item) => self.items.name(item).to_string(),
+            Goods::Rune(rune) => self
+                .magic
+                .rune(rune)
+                .map_or(rune.clone(), |rune| rune.name.clone()),
+            Goods::Upgrade(upgrade) => upgrade.name(),
+        }
+    }
+
+    // loads `<set>.<kind>.ron` files from a folder, the same files the game loads as assets
+    pub fn load(folder: impl AsRef<Path>, set: &str) -> Result<Self, DataError> {
+        let folder = folder.as_ref();
+        let data = Self {
+            magic: load_file(folder, set, "magic")?,
+            battle: load_file(folder, set, "battle")?,
+            monsters: load_file(folder, set, "monsters")?,
+            items: load_file(folder, set, "items")?,
+            shops: load_file(folder, set, "shops")?,
+            cards: load_file(folder, set, "events")?,
+        };
+        data.validate()?;
+        Ok(data)
+    }
+}
+
+fn load_file<T: DeserializeOwned>(folder: &Path, set: &str, kind: &str) -> Result<T, DataError> {
+    let file = format!("{}.{}.ron", set, kind);
+    let text =
+        fs::read_to_string(folder.join(&file)).map_err(|err| DataError::Io(file.clone(), err))?;
+    ron::from_str(&text).map_err(|err| DataError::Parse(file, err))
+}
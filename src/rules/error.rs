@@ -0,0 +1,76 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::fmt;
+
+use super::Slot;
+use crate::board::TileId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    InvalidChoice { tile: TileId, choice: TileId },
+    NotNow,
+    MatchOver,
+    UnknownSpell(String),
+    SpellNotKnown(String),
+    NotEnoughRunes(String),
+    InvalidTarget(Option<usize>),
+    NoItem(usize),
+    UnknownItem(String),
+    NotEquipment(String),
+    NotUsable(String),
+    EmptySlot(Slot),
+    InventoryFull,
+    NoStock(usize),
+    SoldOut(usize),
+    NotEnoughGold(u32),
+    NoRune(String),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::InvalidChoice { tile, choice } => {
+                write!(f, "can't go from tile {} to tile {}", tile, choice)
+            }
+            RuleError::NotNow => write!(f, "that can't be done in this phase of the turn"),
+            RuleError::MatchOver => write!(f, "the match is over"),
+            RuleError::UnknownSpell(spell) => write!(f, "there is no spell {}", spell),
+            RuleError::SpellNotKnown(spell) => write!(f, "spell {} is not in the spellbook", spell),
+            RuleError::NotEnoughRunes(spell) => write!(f, "not enough runes to cast {}", spell),
+            RuleError::InvalidTarget(target) => write!(f, "invalid target {:?}", target),
+            RuleError::NoItem(index) => write!(f, "there is no item in slot {}", index),
+            RuleError::UnknownItem(item) => write!(f, "there is no item {}", item),
+            RuleError::NotEquipment(item) => write!(f, "{} can't be equipped", item),
+            RuleError::NotUsable(item) => write!(f, "{} can't be used", item),
+            RuleError::EmptySlot(slot) => write!(f, "nothing equipped as {}", slot.name()),
+            RuleError::InventoryFull => write!(f, "the inventory is full"),
+            RuleError::NoStock(entry) => write!(f, "the shop has no entry {}", entry),
+            RuleError::SoldOut(entry) => write!(f, "entry {} is sold out", entry),
+            RuleError::NotEnoughGold(price) => write!(f, "not enough gold to pay {}", price),
+            RuleError::NoRune(rune) => write!(f, "there is no {} rune to sell", rune),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
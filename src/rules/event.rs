@@ -0,0 +1,192 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use serde::{Deserialize, Serialize};
+
+use super::{BattleReport, Fighter, Goods, Slot, Weather};
+use crate::{
+    board::{TileId, TileKind},
+    dice::Roll,
+};
+
+// everything a player, an AI or a remote client can ask the match to do
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Command {
+    Roll,
+    Cast {
+        spell: String,
+        target: Option<usize>,
+    },
+    Move,
+    Choose(TileId),
+    Fight,
+    Flee,
+    Equip(usize),
+    Unequip(Slot),
+    UseItem(usize),
+    Buy(usize),
+    SellItem(usize),
+    SellRune(String),
+    LeaveShop,
+}
+
+// what happened when a command was applied, in order
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum GameEvent {
+    TurnStarted {
+        seat: usize,
+        turn: u32,
+    },
+    Rolled {
+        seat: usize,
+        roll: Roll,
+    },
+    SpellCast {
+        seat: usize,
+        spell: String,
+        target: Option<usize>,
+    },
+    RollChanged {
+        seat: usize,
+        roll: Roll,
+    },
+    Teleported {
+        seat: usize,
+        tile: TileId,
+    },
+    Hindered {
+        seat: usize,
+        steps: i32,
+    },
+    Moved {
+        seat: usize,
+        path: Vec<TileId>,
+    },
+    ForkReached {
+        seat: usize,
+        tile: TileId,
+        options: Vec<TileId>,
+    },
+    Landed {
+        seat: usize,
+        tile: TileId,
+        kind: TileKind,
+    },
+    BattleStarted {
+        attacker: Fighter,
+        defender: Fighter,
+    },
+    BattleResolved(Box<BattleReport>),
+    Fled {
+        seat: usize,
+        from: Fighter,
+        damage: u32,
+    },
+    Defeated {
+        fighter: Fighter,
+        name: String,
+    },
+    Looted {
+        seat: usize,
+        gold: u32,
+        items: Vec<String>,
+    },
+    InventoryFull {
+        seat: usize,
+        item: String,
+    },
+    Equipped {
+        seat: usize,
+        item: String,
+        slot: Slot,
+    },
+    Unequipped {
+        seat: usize,
+        item: String,
+        slot: Slot,
+    },
+    ItemUsed {
+        seat: usize,
+        item: String,
+    },
+    Healed {
+        seat: usize,
+        health: u32,
+    },
+    ShopOpened {
+        seat: usize,
+        tile: TileId,
+    },
+    Bought {
+        seat: usize,
+        goods: Goods,
+        price: u32,
+    },
+    Sold {
+        seat: usize,
+        goods: Goods,
+        price: u32,
+    },
+    ShopClosed {
+        seat: usize,
+    },
+    CardDrawn {
+        seat: usize,
+        card: String,
+    },
+    Damaged {
+        seat: usize,
+        damage: u32,
+    },
+    GoldChanged {
+        seat: usize,
+        amount: i32,
+    },
+    WeatherChanged {
+        weather: Option<Weather>,
+    },
+    ShopsRestocked,
+    Eliminated {
+        seat: usize,
+    },
+    MatchEnded {
+        winner: usize,
+    },
+    MonsterSpawned {
+        monster: u32,
+        kind: String,
+        tile: TileId,
+    },
+    MonsterMoved {
+        monster: u32,
+        tile: TileId,
+    },
+    RuneCollected {
+        seat: usize,
+        rune: String,
+    },
+    SpellLearned {
+        seat: usize,
+        spell: String,
+    },
+}
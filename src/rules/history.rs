@@ -0,0 +1,192 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use super::{Command, GameEvent, MatchState, RuleError};
+
+// the decisions of the turn being played, each with the match as it was before it, a command
+// that used the dice, drew a card or ended the turn is final and takes everything before it
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
+pub struct History {
+    undoable: Vec<(MatchState, Command)>,
+}
+
+impl History {
+    pub fn apply(
+        &mut self,
+        state: &mut MatchState,
+        command: Command,
+    ) -> Result<Vec<GameEvent>, RuleError> {
+        let before = state.clone();
+        let events = state.apply(command.clone())?;
+        // a drawn card is seen even when the deck didn't need the dice
+        let committed = state.rng.position() != before.rng.position()
+            || state.deck != before.deck
+            || state.discard != before.discard
+            || events
+                .iter()
+                .any(|event| matches!(event, GameEvent::CardDrawn { .. }))
+            || state.current != before.current
+            || state.turn != before.turn
+            || state.winner().is_some();
+        if committed {
+            self.undoable.clear();
+        } else {
+            self.undoable.push((before, command));
+        }
+        Ok(events)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undoable.is_empty()
+    }
+
+    // puts the match back as it was before the last decision and returns it
+    pub fn undo(&mut self, state: &mut MatchState) -> Option<Command> {
+        let (before, command) = self.undoable.pop()?;
+        *state = before;
+        Some(command)
+    }
+
+    // something outside of the commands moved the match on, like a battle
+    pub fn commit(&mut self) {
+        self.undoable.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        board::{Board, Tile, TileId, TileKind},
+        dice::Roll,
+        rules::{
+            cards::{CardDef, CardEffect, CardKind, CardStep, CardTarget},
+            GameData, MatchRules, Phase,
+        },
+    };
+
+    // the start, a fork and two plain tiles that go back to the start
+    fn board() -> Board {
+        let tile = |index, kind, next: &[usize]| Tile {
+            id: TileId(index),
+            kind,
+            position: (index as f32, 0.),
+            next: next.iter().map(|next| TileId(*next)).collect(),
+        };
+        Board {
+            name: "Test".into(),
+            tiles: vec![
+                tile(0, TileKind::Start, &[1]),
+                tile(1, TileKind::Shortcut, &[2, 3]),
+                tile(2, TileKind::Battle, &[0]),
+                tile(3, TileKind::Event, &[0]),
+            ],
+        }
+    }
+
+    fn state() -> MatchState {
+        let mut data = GameData::default();
+        data.cards.cards.push(CardDef {
+            id: "gift".into(),
+            name: "Gift".into(),
+            kind: CardKind::Blessing,
+            text: String::new(),
+            effects: vec![CardStep {
+                target: CardTarget::Drawer,
+                effect: CardEffect::Gold(5),
+            }],
+            copies: 2,
+        });
+        let names = ["Ana".to_string(), "Bo".to_string()];
+        MatchState::new(board(), Arc::new(data), MatchRules::default(), &names, 5)
+    }
+
+    fn rolled(state: &mut MatchState, total: u8) {
+        state.phase = Phase::Rolled {
+            roll: Roll {
+                faces: vec![total],
+                bonus: 0,
+            },
+        };
+    }
+
+    #[test]
+    fn undo_a_plain_move() {
+        let mut state = state();
+        let mut history = History::default();
+        rolled(&mut state, 2);
+        let before = state.clone();
+        history.apply(&mut state, Command::Move).unwrap();
+        assert_eq!(state.players[0].tile, TileId(1));
+        assert!(history.can_undo());
+        assert_eq!(history.undo(&mut state), Some(Command::Move));
+        assert_eq!(state, before);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn no_undo_after_a_roll() {
+        let mut state = state();
+        let mut history = History::default();
+        history.apply(&mut state, Command::Roll).unwrap();
+        assert!(!history.can_undo());
+        assert_eq!(history.undo(&mut state), None);
+    }
+
+    // the card is seen, even when landing started a battle and the turn goes on
+    #[test]
+    fn no_undo_after_a_card_draw() {
+        let mut state = state();
+        let mut history = History::default();
+        state.players[1].tile = TileId(3);
+        rolled(&mut state, 2);
+        history.apply(&mut state, Command::Move).unwrap();
+        let events = history
+            .apply(&mut state, Command::Choose(TileId(3)))
+            .unwrap();
+        let drawn = |event: &GameEvent| matches!(event, GameEvent::CardDrawn { .. });
+        let battle = |event: &GameEvent| matches!(event, GameEvent::BattleStarted { .. });
+        assert!(events.iter().any(drawn) && events.iter().any(battle));
+        assert!(matches!(state.phase, Phase::Battle { .. }));
+        assert!(!history.can_undo());
+        assert_eq!(history.undo(&mut state), None);
+    }
+
+    #[test]
+    fn no_undo_after_the_turn_ends() {
+        let mut state = state();
+        let mut history = History::default();
+        rolled(&mut state, 2);
+        history.apply(&mut state, Command::Move).unwrap();
+        assert!(history.can_undo());
+        history
+            .apply(&mut state, Command::Choose(TileId(2)))
+            .unwrap();
+        assert_eq!(state.current, 1);
+        assert!(!history.can_undo());
+        assert_eq!(history.undo(&mut state), None);
+    }
+}
@@ -0,0 +1,101 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub enum Slot {
+    Weapon,
+    Armor,
+    Trinket,
+}
+
+impl Slot {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Slot::Weapon => "Weapon",
+            Slot::Armor => "Armor",
+            Slot::Trinket => "Trinket",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ItemDef {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    // items without a slot are used up instead of equipped
+    #[serde(default)]
+    pub slot: Option<Slot>,
+    #[serde(default)]
+    pub attack: i32,
+    #[serde(default)]
+    pub defense: i32,
+    #[serde(default)]
+    pub attack_dice: u8,
+    #[serde(default)]
+    pub defense_dice: u8,
+    #[serde(default)]
+    pub heal: u32,
+    #[serde(default)]
+    pub price: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ItemData {
+    pub items: Vec<ItemDef>,
+    pub inventory_slots: usize,
+}
+
+impl Default for ItemData {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            inventory_slots: 6,
+        }
+    }
+}
+
+impl ItemData {
+    pub fn item(&self, id: &str) -> Option<&ItemDef> {
+        self.items.iter().find(|item| item.id == id)
+    }
+
+    // the name to show for an item id, falling back to the id itself
+    pub fn name<'a>(&'a self, id: &'a str) -> &'a str {
+        self.item(id).map_or(id, |item| item.name.as_str())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(item) = self
+            .items
+            .iter()
+            .find(|item| item.slot.is_none() && item.heal == 0)
+        {
+            return Err(format!("item {} can't be equipped or used", item.id));
+        }
+        Ok(())
+    }
+}
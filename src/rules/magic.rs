@@ -0,0 +1,129 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::collections::BTreeMap;
+
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::board::TileKind;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RuneDef {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    #[serde(default)]
+    pub price: u32,
+    // battle bonus for each rune of this kind a player holds
+    #[serde(default)]
+    pub attack: i32,
+    #[serde(default)]
+    pub defense: i32,
+    // holding one of each key rune wins a key runes match
+    #[serde(default)]
+    pub key: bool,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum SpellEffect {
+    Reroll,
+    AddPips(i32),
+    ForceFace(u8),
+    Teleport(TileKind),
+    Hinder(i32),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SpellTiming {
+    BeforeRoll,
+    AfterRoll,
+}
+
+impl SpellEffect {
+    pub fn timing(&self) -> SpellTiming {
+        match self {
+            SpellEffect::Reroll | SpellEffect::AddPips(_) | SpellEffect::ForceFace(_) => {
+                SpellTiming::AfterRoll
+            }
+            SpellEffect::Teleport(_) | SpellEffect::Hinder(_) => SpellTiming::BeforeRoll,
+        }
+    }
+
+    pub fn needs_target(&self) -> bool {
+        matches!(self, SpellEffect::Hinder(_))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SpellDef {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub cost: BTreeMap<String, u32>,
+    pub effect: SpellEffect,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct MagicData {
+    pub runes: Vec<RuneDef>,
+    pub spells: Vec<SpellDef>,
+    #[serde(default)]
+    pub starting_spells: Vec<String>,
+}
+
+impl MagicData {
+    pub fn rune(&self, id: &str) -> Option<&RuneDef> {
+        self.runes.iter().find(|rune| rune.id == id)
+    }
+
+    pub fn spell(&self, id: &str) -> Option<&SpellDef> {
+        self.spells.iter().find(|spell| spell.id == id)
+    }
+
+    pub fn random_rune(&self, rng: &mut impl Rng) -> Option<&RuneDef> {
+        self.runes.choose_weighted(rng, |rune| rune.weight).ok()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for spell in &self.spells {
+            if let Some(rune) = spell.cost.keys().find(|rune| self.rune(rune).is_none()) {
+                return Err(format!("spell {} costs unknown rune {}", spell.id, rune));
+            }
+        }
+        if let Some(spell) = self
+            .starting_spells
+            .iter()
+            .find(|spell| self.spell(spell).is_none())
+        {
+            return Err(format!("unknown starting spell {}", spell));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+mod battle;
+pub use battle::{
+    damage, odds, resolve, BattleOdds, BattleReport, BattleRules, Combatant, Exchange, Fighter,
+    Modifier,
+};
+
+mod cards;
+pub use cards::{CardData, CardEffect, CardTarget, Weather};
+
+mod data;
+pub use data::{DataError, GameData};
+
+mod error;
+pub use error::RuleError;
+
+mod event;
+pub use event::{Command, GameEvent};
+
+mod history;
+pub use history::History;
+
+mod items;
+pub use items::{ItemData, ItemDef, Slot};
+
+mod magic;
+pub use magic::{MagicData, SpellDef, SpellEffect, SpellTiming};
+
+mod monster;
+pub use monster::{Behaviour, Monster, MonsterData};
+
+mod movement;
+pub use movement::{walk, Walk};
+
+mod player;
+pub use player::{Player, Record, Stats};
+
+mod replay;
+pub use replay::{Replay, ReplayError};
+
+mod shop;
+pub use shop::{Goods, Shop, ShopData, ShopDef, Upgrade};
+
+mod state;
+pub use state::{MatchState, Phase, MOVEMENT_DICE};
+
+mod victory;
+pub use victory::{MatchRules, Standing, Victory};
@@ -0,0 +1,187 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::{Combatant, Fighter, Modifier};
+use crate::{board::TileId, dice::DicePool};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Ability {
+    Fierce(i32),
+    Armored(i32),
+    Regenerate(u32),
+    FirstStrike,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Behaviour {
+    Guard,
+    Roam,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LootEntry {
+    pub item: String,
+    // percentage
+    pub chance: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MonsterDef {
+    pub id: String,
+    pub name: String,
+    pub health: u32,
+    pub attack: DicePool,
+    pub defense: DicePool,
+    #[serde(default)]
+    pub abilities: Vec<Ability>,
+    #[serde(default)]
+    pub loot: Vec<LootEntry>,
+    #[serde(default)]
+    pub gold: u32,
+    pub behaviour: Behaviour,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl MonsterDef {
+    pub fn regenerate(&self) -> u32 {
+        self.abilities
+            .iter()
+            .map(|ability| match ability {
+                Ability::Regenerate(health) => *health,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    pub fn roll_loot(&self, rng: &mut impl Rng) -> Vec<String> {
+        self.loot
+            .iter()
+            .filter(|entry| rng.gen_range(0..100) < entry.chance)
+            .map(|entry| entry.item.clone())
+            .collect()
+    }
+}
+
+// how many monsters are on the board and how often new ones show up
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SpawnRules {
+    pub initial: u32,
+    pub max: u32,
+    pub every_rounds: u32,
+}
+
+impl Default for SpawnRules {
+    fn default() -> Self {
+        Self {
+            initial: 3,
+            max: 5,
+            every_rounds: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct MonsterData {
+    pub monsters: Vec<MonsterDef>,
+    #[serde(default)]
+    pub spawn: SpawnRules,
+}
+
+impl MonsterData {
+    pub fn monster(&self, id: &str) -> Option<&MonsterDef> {
+        self.monsters.iter().find(|monster| monster.id == id)
+    }
+
+    pub fn random_monster(&self, rng: &mut impl Rng) -> Option<&MonsterDef> {
+        self.monsters
+            .choose_weighted(rng, |monster| monster.weight)
+            .ok()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for monster in &self.monsters {
+            if monster.health == 0 {
+                return Err(format!("monster {} has no health", monster.id));
+            }
+            if let Some(entry) = monster.loot.iter().find(|entry| entry.chance > 100) {
+                return Err(format!(
+                    "monster {} drops {} more than always",
+                    monster.id, entry.item
+                ));
+            }
+        }
+        if self.spawn.initial > self.spawn.max {
+            return Err("more initial monsters than the maximum".into());
+        }
+        Ok(())
+    }
+}
+
+// a monster on the board
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Monster {
+    pub id: u32,
+    pub kind: String,
+    pub tile: TileId,
+    pub health: u32,
+}
+
+impl Monster {
+    pub fn combatant(&self, def: &MonsterDef) -> Combatant {
+        let modifiers = def
+            .abilities
+            .iter()
+            .filter_map(|ability| match ability {
+                Ability::Fierce(attack) => Some(Modifier {
+                    source: "Fierce".into(),
+                    attack: *attack,
+                    ..Default::default()
+                }),
+                Ability::Armored(defense) => Some(Modifier {
+                    source: "Armored".into(),
+                    defense: *defense,
+                    ..Default::default()
+                }),
+                _ => None,
+            })
+            .collect();
+        Combatant {
+            fighter: Fighter::Monster(self.id),
+            name: def.name.clone(),
+            health: self.health,
+            max_health: def.health,
+            attack: def.attack,
+            defense: def.defense,
+            modifiers,
+            first_strike: def.abilities.contains(&Ability::FirstStrike),
+        }
+    }
+}
@@ -0,0 +1,210 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use crate::board::{Board, TileId};
+
+use super::RuleError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Walk {
+    Arrived {
+        path: Vec<TileId>,
+    },
+    Fork {
+        path: Vec<TileId>,
+        remaining: u32,
+        options: Vec<TileId>,
+    },
+}
+
+impl Walk {
+    pub fn path(&self) -> &[TileId] {
+        match self {
+            Walk::Arrived { path } | Walk::Fork { path, .. } => path,
+        }
+    }
+}
+
+// the path does not include the start tile, at every fork a choice is taken from the list
+// in order, when we run out of choices the walk stops on the fork asking for one
+pub fn walk(
+    board: &Board,
+    start: TileId,
+    steps: u32,
+    choices: &[TileId],
+) -> Result<Walk, RuleError> {
+    let mut path = Vec::with_capacity(steps as usize);
+    let mut choices = choices.iter();
+    let mut current = start;
+    for step in 0..steps {
+        let next = board.next(current);
+        current = match next {
+            [only] => *only,
+            options => match choices.next() {
+                Some(choice) if options.contains(choice) => *choice,
+                Some(choice) => {
+                    return Err(RuleError::InvalidChoice {
+                        tile: current,
+                        choice: *choice,
+                    })
+                }
+                None => {
+                    return Ok(Walk::Fork {
+                        path,
+                        remaining: steps - step,
+                        options: options.to_vec(),
+                    })
+                }
+            },
+        };
+        path.push(current);
+    }
+    Ok(Walk::Arrived { path })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        board::{Tile, TileKind},
+        dice::Roll,
+        rules::{Command, Fighter, GameData, MatchRules, MatchState, Monster, Phase},
+    };
+
+    // a loop of five tiles from the start with a fork on the second one, the left way is
+    // two tiles long and the right one only one
+    fn board() -> Board {
+        let tile = |index, kind, next: &[usize]| Tile {
+            id: TileId(index),
+            kind,
+            position: (index as f32, 0.),
+            next: next.iter().map(|next| TileId(*next)).collect(),
+        };
+        Board {
+            name: "Test".into(),
+            tiles: vec![
+                tile(0, TileKind::Start, &[1]),
+                tile(1, TileKind::Battle, &[2]),
+                tile(2, TileKind::Shortcut, &[3, 5]),
+                tile(3, TileKind::Battle, &[4]),
+                tile(4, TileKind::Battle, &[0]),
+                tile(5, TileKind::Battle, &[0]),
+            ],
+        }
+    }
+
+    fn path(tiles: &[usize]) -> Vec<TileId> {
+        tiles.iter().map(|tile| TileId(*tile)).collect()
+    }
+
+    #[test]
+    fn exact_length_path() {
+        let two = walk(&board(), TileId(0), 2, &[]).unwrap();
+        assert_eq!(
+            two,
+            Walk::Arrived {
+                path: path(&[1, 2])
+            }
+        );
+        let none = walk(&board(), TileId(3), 0, &[]).unwrap();
+        assert_eq!(none, Walk::Arrived { path: Vec::new() });
+    }
+
+    #[test]
+    fn fork_needs_a_choice() {
+        let board = board();
+        let stopped = walk(&board, TileId(0), 4, &[]).unwrap();
+        assert_eq!(
+            stopped,
+            Walk::Fork {
+                path: path(&[1, 2]),
+                remaining: 2,
+                options: path(&[3, 5]),
+            }
+        );
+        let chosen = walk(&board, TileId(2), 2, &[TileId(5)]).unwrap();
+        assert_eq!(
+            chosen,
+            Walk::Arrived {
+                path: path(&[5, 0])
+            }
+        );
+        assert_eq!(
+            walk(&board, TileId(2), 2, &[TileId(4)]),
+            Err(RuleError::InvalidChoice {
+                tile: TileId(2),
+                choice: TileId(4),
+            })
+        );
+    }
+
+    #[test]
+    fn passing_the_start_goes_on() {
+        let passed = walk(&board(), TileId(4), 3, &[]).unwrap();
+        assert_eq!(
+            passed,
+            Walk::Arrived {
+                path: path(&[0, 1, 2])
+            }
+        );
+    }
+
+    // only the tile the walk ends on starts a battle, not the ones passed on the way
+    #[test]
+    fn stopping_on_a_monster_fights_it() {
+        let play = |total| {
+            let names = ["Ana".to_string(), "Bo".to_string()];
+            let data = Arc::new(GameData::default());
+            let mut state = MatchState::new(board(), data, MatchRules::default(), &names, 5);
+            state.monsters = vec![Monster {
+                id: 7,
+                kind: "wolf".into(),
+                tile: TileId(1),
+                health: 3,
+            }];
+            state.phase = Phase::Rolled {
+                roll: Roll {
+                    faces: vec![total],
+                    bonus: 0,
+                },
+            };
+            state.apply(Command::Move).unwrap();
+            state
+        };
+        let stopped = play(1);
+        assert_eq!(stopped.players[0].tile, TileId(1));
+        assert_eq!(
+            stopped.phase,
+            Phase::Battle {
+                attacker: Fighter::Player(0),
+                defender: Fighter::Monster(7),
+            }
+        );
+        let passed = play(2);
+        assert_eq!(passed.players[0].tile, TileId(2));
+        assert_eq!(passed.current, 1);
+        assert_eq!(passed.phase, Phase::Roll);
+    }
+}
@@ -0,0 +1,154 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ItemDef, Slot, SpellDef, Upgrade};
+use crate::{board::TileId, dice::DicePool};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Stats {
+    pub max_health: u32,
+    pub attack: DicePool,
+    pub defense: DicePool,
+}
+
+// what a player did during the match, for the results
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct Record {
+    pub steps: u32,
+    pub battles_won: u32,
+    pub monsters_defeated: u32,
+    pub defeats: u32,
+    pub cards_drawn: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Player {
+    pub seat: usize,
+    pub name: String,
+    pub tile: TileId,
+    pub runes: BTreeMap<String, u32>,
+    pub spellbook: Vec<String>,
+    pub hindered: i32,
+    pub stats: Stats,
+    pub health: u32,
+    pub gold: u32,
+    pub inventory: Vec<String>,
+    pub equipment: BTreeMap<Slot, String>,
+    pub record: Record,
+    pub out: bool,
+}
+
+impl Player {
+    pub fn new(seat: usize, name: impl Into<String>, tile: TileId, stats: Stats) -> Self {
+        Self {
+            seat,
+            name: name.into(),
+            tile,
+            runes: BTreeMap::new(),
+            spellbook: Vec::new(),
+            hindered: 0,
+            health: stats.max_health,
+            stats,
+            gold: 0,
+            inventory: Vec::new(),
+            equipment: BTreeMap::new(),
+            record: Record::default(),
+            out: false,
+        }
+    }
+
+    pub fn heal(&mut self, health: u32) -> u32 {
+        let healed = health.min(self.stats.max_health.saturating_sub(self.health));
+        self.health += healed;
+        healed
+    }
+
+    // puts the item in its slot, what was there goes back to the inventory
+    pub fn equip(&mut self, index: usize, item: &ItemDef) -> Option<String> {
+        let slot = item.slot?;
+        let id = self.inventory.remove(index);
+        let old = self.equipment.insert(slot, id);
+        if let Some(old) = &old {
+            self.inventory.push(old.clone());
+        }
+        old
+    }
+
+    pub fn rune_count(&self) -> u32 {
+        self.runes.values().sum()
+    }
+
+    pub fn add_rune(&mut self, rune: &str) {
+        *self.runes.entry(rune.into()).or_default() += 1;
+    }
+
+    pub fn take_rune(&mut self, rune: &str) -> bool {
+        match self.runes.get_mut(rune) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                if *count == 0 {
+                    self.runes.remove(rune);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn upgrade(&mut self, upgrade: Upgrade) {
+        match upgrade {
+            Upgrade::AttackDie => self.stats.attack.dice += 1,
+            Upgrade::DefenseDie => self.stats.defense.dice += 1,
+            Upgrade::AttackBonus(bonus) => self.stats.attack.bonus += bonus,
+            Upgrade::DefenseBonus(bonus) => self.stats.defense.bonus += bonus,
+            Upgrade::MaxHealth(health) => {
+                self.stats.max_health += health;
+                self.health += health;
+            }
+        }
+    }
+
+    pub fn knows(&self, spell: &str) -> bool {
+        self.spellbook.iter().any(|known| known == spell)
+    }
+
+    pub fn can_afford(&self, spell: &SpellDef) -> bool {
+        spell
+            .cost
+            .iter()
+            .all(|(rune, count)| self.runes.get(rune).copied().unwrap_or_default() >= *count)
+    }
+
+    pub fn pay(&mut self, spell: &SpellDef) {
+        for (rune, count) in &spell.cost {
+            if let Some(owned) = self.runes.get_mut(rune) {
+                *owned = owned.saturating_sub(*count);
+            }
+        }
+        self.runes.retain(|_, count| *count > 0);
+    }
+}
@@ -0,0 +1,243 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::{fmt, fs, io, path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Command, GameData, MatchRules, MatchState, RuleError};
+use crate::board::Board;
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(String, io::Error),
+    Parse(String, ron::error::SpannedError),
+    Rejected(usize, Command, RuleError),
+    Diverged { expected: u64, found: u64 },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(file, err) => write!(f, "can't access {}: {}", file, err),
+            ReplayError::Parse(file, err) => write!(f, "can't parse {}: {}", file, err),
+            ReplayError::Rejected(index, command, err) => {
+                write!(f, "command {} {:?} was rejected: {}", index, command, err)
+            }
+            ReplayError::Diverged { expected, found } => write!(
+                f,
+                "the match ended with checksum {:016x} instead of {:016x}",
+                found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+// everything needed to play a match again, how it was set up and every command in order,
+// the data goes along so a replay still plays after the data files are tuned
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
+pub struct Replay {
+    pub board: Board,
+    pub data: GameData,
+    pub rules: MatchRules,
+    pub names: Vec<String>,
+    pub seed: u64,
+    pub commands: Vec<Command>,
+    // of the state after the last command, playing the replay again must end on the same
+    pub checksum: u64,
+}
+
+impl Replay {
+    // starts recording a match that no command was applied to yet
+    pub fn start(state: &MatchState) -> Self {
+        Self {
+            board: state.board.clone(),
+            data: state.data.as_ref().clone(),
+            rules: state.rules,
+            names: state
+                .players
+                .iter()
+                .map(|player| player.name.clone())
+                .collect(),
+            seed: state.rng.seed(),
+            commands: Vec::new(),
+            checksum: state.checksum(),
+        }
+    }
+
+    // only commands the match accepted belong here, with the state they left behind
+    pub fn record(&mut self, command: Command, state: &MatchState) {
+        self.commands.push(command);
+        self.checksum = state.checksum();
+    }
+
+    // an undone command never happened as far as the replay goes
+    pub fn undo(&mut self, state: &MatchState) {
+        self.commands.pop();
+        self.checksum = state.checksum();
+    }
+
+    // the match as it was before the first command
+    pub fn initial_state(&self) -> MatchState {
+        MatchState::new(
+            self.board.clone(),
+            Arc::new(self.data.clone()),
+            self.rules,
+            &self.names,
+            self.seed,
+        )
+    }
+
+    // plays every command again and checks the match ends as it did when recorded
+    pub fn verify(&self) -> Result<MatchState, ReplayError> {
+        let mut state = self.initial_state();
+        for (index, command) in self.commands.iter().enumerate() {
+            state
+                .apply(command.clone())
+                .map_err(|err| ReplayError::Rejected(index, command.clone(), err))?;
+        }
+        let found = state.checksum();
+        if found != self.checksum {
+            return Err(ReplayError::Diverged {
+                expected: self.checksum,
+                found,
+            });
+        }
+        Ok(state)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let path = path.as_ref();
+        let file = path.display().to_string();
+        let text = fs::read_to_string(path).map_err(|err| ReplayError::Io(file.clone(), err))?;
+        ron::from_str(&text).map_err(|err| ReplayError::Parse(file, err))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ReplayError> {
+        let path = path.as_ref();
+        let file = path.display().to_string();
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).map_err(|err| ReplayError::Io(file.clone(), err))?;
+        }
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .expect("replay should serialize");
+        fs::write(path, text).map_err(|err| ReplayError::Io(file, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use super::*;
+    use crate::{
+        ai::candidates,
+        rules::{GameEvent, Phase},
+    };
+
+    // a match this long has seen everything the replay has to cover
+    const MAX_COMMANDS: usize = 2000;
+
+    // plays the classic board fleeing the first battle and fighting every other one,
+    // buying whatever a shop sells that can be paid for, until the match had a fight, a
+    // flee, a purchase and a card drawn
+    fn recorded(seed: u64) -> Replay {
+        let assets = concat!(env!("CARGO_MANIFEST_DIR"), "/assets");
+        let board = Board::load(format!("{}/boards/classic.board.ron", assets)).unwrap();
+        let data = GameData::load(format!("{}/data", assets), "default").unwrap();
+        let names = ["One".to_string(), "Two".to_string()];
+        let mut state = MatchState::new(board, Arc::new(data), MatchRules::default(), &names, seed);
+        let mut replay = Replay::start(&state);
+        let (mut fought, mut fled, mut bought, mut drawn) = (false, false, false, false);
+        while !(fought && fled && bought && drawn) {
+            assert!(
+                replay.commands.len() < MAX_COMMANDS,
+                "seed {} saw too little",
+                seed
+            );
+            let command = match &state.phase {
+                Phase::Roll => Command::Roll,
+                Phase::Rolled { .. } => Command::Move,
+                Phase::Choose { options, .. } => {
+                    Command::Choose(options[state.turn as usize % options.len()])
+                }
+                Phase::Battle { .. } if fled => Command::Fight,
+                Phase::Battle { .. } => Command::Flee,
+                Phase::Shop { .. } => candidates(&state, state.current)
+                    .into_iter()
+                    .find(|command| matches!(command, Command::Buy(_)))
+                    .unwrap_or(Command::LeaveShop),
+                Phase::Over { .. } => panic!("seed {} ended too soon", seed),
+            };
+            let events = state.apply(command.clone()).unwrap();
+            for event in &events {
+                match event {
+                    GameEvent::BattleResolved(_) => fought = true,
+                    GameEvent::Fled { .. } => fled = true,
+                    GameEvent::Bought { .. } => bought = true,
+                    GameEvent::CardDrawn { .. } => drawn = true,
+                    _ => {}
+                }
+            }
+            replay.record(command, &state);
+        }
+        replay
+    }
+
+    // the replay goes through the file format before it is played again
+    fn saved(replay: &Replay) -> Replay {
+        let path = std::env::temp_dir().join(format!("dice_master_{}.replay.ron", process::id()));
+        replay.save(&path).unwrap();
+        let loaded = Replay::load(&path);
+        fs::remove_file(&path).unwrap();
+        loaded.unwrap()
+    }
+
+    #[test]
+    fn recorded_replay_plays_again() {
+        let replay = saved(&recorded(1));
+        let state = replay.verify().unwrap_or_else(|err| panic!("{}", err));
+        assert_eq!(state.checksum(), replay.checksum);
+        assert_eq!(replay, saved(&recorded(1)));
+    }
+
+    #[test]
+    fn wrong_checksum_diverges() {
+        let mut replay = recorded(1);
+        replay.checksum ^= 1;
+        assert!(matches!(
+            replay.verify(),
+            Err(ReplayError::Diverged { expected, .. }) if expected == replay.checksum
+        ));
+    }
+
+    #[test]
+    fn missing_command_diverges() {
+        let mut replay = recorded(1);
+        replay.commands.pop();
+        assert!(matches!(replay.verify(), Err(ReplayError::Diverged { .. })));
+    }
+}
@@ -0,0 +1,138 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use serde::{Deserialize, Serialize};
+
+use super::{ItemData, MagicData};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Upgrade {
+    AttackDie,
+    DefenseDie,
+    AttackBonus(i32),
+    DefenseBonus(i32),
+    MaxHealth(u32),
+}
+
+impl Upgrade {
+    pub fn name(&self) -> String {
+        match self {
+            Upgrade::AttackDie => "Extra attack die".into(),
+            Upgrade::DefenseDie => "Extra defence die".into(),
+            Upgrade::AttackBonus(bonus) => format!("Attack {:+}", bonus),
+            Upgrade::DefenseBonus(bonus) => format!("Defence {:+}", bonus),
+            Upgrade::MaxHealth(health) => format!("Max health +{}", health),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Goods {
+    Item(String),
+    Rune(String),
+    Upgrade(Upgrade),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StockEntry {
+    pub goods: Goods,
+    // items and runes use their own price when this is not set
+    #[serde(default)]
+    pub price: Option<u32>,
+    pub quantity: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ShopDef {
+    pub name: String,
+    pub stock: Vec<StockEntry>,
+    // percentage applied to every price in this town
+    #[serde(default = "default_markup")]
+    pub markup: u32,
+    pub restock_rounds: u32,
+}
+
+fn default_markup() -> u32 {
+    100
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct ShopData {
+    // towns take the shops in order, going around when there are more towns than shops
+    pub shops: Vec<ShopDef>,
+    // percentage of the base price paid when selling
+    pub sell_percent: u32,
+}
+
+impl ShopData {
+    pub fn base_price(&self, goods: &Goods, items: &ItemData, magic: &MagicData) -> Option<u32> {
+        match goods {
+            Goods::Item(item) => items.item(item).map(|item| item.price),
+            Goods::Rune(rune) => magic.rune(rune).map(|rune| rune.price),
+            Goods::Upgrade(_) => None,
+        }
+    }
+
+    pub fn sell_price(&self, goods: &Goods, items: &ItemData, magic: &MagicData) -> u32 {
+        self.base_price(goods, items, magic).unwrap_or_default() * self.sell_percent / 100
+    }
+
+    pub fn validate(&self, items: &ItemData, magic: &MagicData) -> Result<(), String> {
+        for shop in &self.shops {
+            for entry in &shop.stock {
+                let known = match &entry.goods {
+                    Goods::Item(item) => items.item(item).is_some(),
+                    Goods::Rune(rune) => magic.rune(rune).is_some(),
+                    Goods::Upgrade(_) => entry.price.is_some(),
+                };
+                if !known {
+                    return Err(format!(
+                        "shop {} sells unknown {:?}",
+                        shop.name, entry.goods
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// a town shop during a match
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Shop {
+    pub def: usize,
+    pub quantities: Vec<u32>,
+}
+
+impl Shop {
+    pub fn new(def: usize, shop: &ShopDef) -> Self {
+        Self {
+            def,
+            quantities: shop.stock.iter().map(|entry| entry.quantity).collect(),
+        }
+    }
+
+    pub fn restock(&mut self, shop: &ShopDef) {
+        self.quantities = shop.stock.iter().map(|entry| entry.quantity).collect();
+    }
+}
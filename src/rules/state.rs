@@ -0,0 +1,1415 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::{cmp::Reverse, collections::BTreeMap, sync::Arc};
+
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Board, TileId, TileKind},
+    dice::{DicePool, Roll},
+    rng::GameRng,
+};
+
+use super::{
+    resolve, walk, Behaviour, CardEffect, CardTarget, Combatant, Command, Fighter, GameData,
+    GameEvent, Goods, ItemDef, MatchRules, Modifier, Monster, Player, RuleError, Shop, ShopDef,
+    Slot, SpellDef, SpellEffect, SpellTiming, Standing, Victory, Walk, Weather,
+};
+
+pub const MOVEMENT_DICE: DicePool = DicePool::new(2, 6);
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Phase {
+    Roll,
+    Rolled {
+        roll: Roll,
+    },
+    Choose {
+        remaining: u32,
+        options: Vec<TileId>,
+    },
+    Battle {
+        attacker: Fighter,
+        defender: Fighter,
+    },
+    Shop {
+        tile: TileId,
+    },
+    Over {
+        winner: usize,
+    },
+}
+
+// the whole state of a match, it only changes by applying commands so the same seed and
+// the same commands always give the same match
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
+pub struct MatchState {
+    pub board: Board,
+    pub data: Arc<GameData>,
+    pub rules: MatchRules,
+    pub players: Vec<Player>,
+    pub monsters: Vec<Monster>,
+    pub next_monster: u32,
+    pub shops: BTreeMap<TileId, Shop>,
+    pub deck: Vec<String>,
+    pub discard: Vec<String>,
+    pub weather: Option<Weather>,
+    pub current: usize,
+    pub turn: u32,
+    pub phase: Phase,
+    pub rng: GameRng,
+}
+
+impl MatchState {
+    pub fn new(
+        board: Board,
+        data: Arc<GameData>,
+        rules: MatchRules,
+        names: &[String],
+        seed: u64,
+    ) -> Self {
+        let start = board.start();
+        let players = names
+            .iter()
+            .enumerate()
+            .map(|(seat, name)| Player {
+                spellbook: data.magic.starting_spells.clone(),
+                ..Player::new(seat, name.clone(), start, data.battle.starting_stats())
+            })
+            .collect();
+        let shop_defs = &data.shops.shops;
+        let shops = board
+            .tiles_of(TileKind::Town)
+            .enumerate()
+            .filter(|_| !shop_defs.is_empty())
+            .map(|(index, tile)| {
+                let def = index % shop_defs.len();
+                (tile.id, Shop::new(def, &shop_defs[def]))
+            })
+            .collect();
+        let deck = data.cards.deck();
+        let mut state = Self {
+            board,
+            data,
+            rules,
+            players,
+            monsters: Vec::new(),
+            next_monster: 0,
+            shops,
+            deck,
+            discard: Vec::new(),
+            weather: None,
+            current: 0,
+            turn: 1,
+            phase: Phase::Roll,
+            rng: GameRng::from_seed(seed),
+        };
+        state.deck.shuffle(&mut state.rng);
+        for _ in 0..state.data.monsters.spawn.initial {
+            state.spawn_monster(&mut Vec::new());
+        }
+        state
+    }
+
+    pub fn current_player(&self) -> &Player {
+        &self.players[self.current]
+    }
+
+    // the dice the current player moves with, slowed by spells and the weather
+    pub fn movement_pool(&self) -> DicePool {
+        let weather = self.weather.as_ref().map_or(0, |weather| weather.movement);
+        MOVEMENT_DICE.with_bonus(weather - self.current_player().hindered)
+    }
+
+    // a fingerprint of everything commands change, two matches that played out the same
+    // way have the same checksum
+    pub fn checksum(&self) -> u64 {
+        #[derive(Serialize)]
+        struct Fingerprint<'a> {
+            players: &'a [Player],
+            monsters: &'a [Monster],
+            next_monster: u32,
+            shops: &'a BTreeMap<TileId, Shop>,
+            deck: &'a [String],
+            discard: &'a [String],
+            weather: &'a Option<Weather>,
+            current: usize,
+            turn: u32,
+            phase: &'a Phase,
+        }
+        let fingerprint = ron::to_string(&Fingerprint {
+            players: &self.players,
+            monsters: &self.monsters,
+            next_monster: self.next_monster,
+            shops: &self.shops,
+            deck: &self.deck,
+            discard: &self.discard,
+            weather: &self.weather,
+            current: self.current,
+            turn: self.turn,
+            phase: &self.phase,
+        })
+        .expect("match state should serialize");
+        // fnv-1a, so the checksum never changes between builds
+        fingerprint
+            .as_bytes()
+            .iter()
+            .chain(&self.rng.position().to_le_bytes())
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    pub fn monster(&self, id: u32) -> Option<&Monster> {
+        self.monsters.iter().find(|monster| monster.id == id)
+    }
+
+    pub fn start_events(&self) -> Vec<GameEvent> {
+        let mut events = self
+            .monsters
+            .iter()
+            .map(|monster| GameEvent::MonsterSpawned {
+                monster: monster.id,
+                kind: monster.kind.clone(),
+                tile: monster.tile,
+            })
+            .collect::<Vec<_>>();
+        events.push(GameEvent::TurnStarted {
+            seat: self.current,
+            turn: self.turn,
+        });
+        events
+    }
+
+    // spells the current player could cast right now
+    pub fn castable_spells(&self) -> Vec<&SpellDef> {
+        let timing = match self.phase {
+            Phase::Roll => SpellTiming::BeforeRoll,
+            Phase::Rolled { .. } => SpellTiming::AfterRoll,
+            _ => return Vec::new(),
+        };
+        let player = self.current_player();
+        player
+            .spellbook
+            .iter()
+            .filter_map(|spell| self.data.magic.spell(spell))
+            .filter(|spell| spell.effect.timing() == timing && player.can_afford(spell))
+            .collect()
+    }
+
+    pub fn apply(&mut self, command: Command) -> Result<Vec<GameEvent>, RuleError> {
+        let mut events = Vec::new();
+        match (&self.phase, command) {
+            (Phase::Roll, Command::Roll) => {
+                let roll = self.movement_pool().roll(&mut self.rng);
+                self.players[self.current].hindered = 0;
+                events.push(GameEvent::Rolled {
+                    seat: self.current,
+                    roll: roll.clone(),
+                });
+                self.phase = Phase::Rolled { roll };
+            }
+            (Phase::Roll | Phase::Rolled { .. }, Command::Cast { spell, target }) => {
+                self.cast(&spell, target, &mut events)?;
+            }
+            (Phase::Roll | Phase::Rolled { .. }, Command::Equip(index)) => {
+                self.equip(index, &mut events)?;
+            }
+            (Phase::Roll | Phase::Rolled { .. }, Command::Unequip(slot)) => {
+                self.unequip(slot, &mut events)?;
+            }
+            (Phase::Roll | Phase::Rolled { .. }, Command::UseItem(index)) => {
+                self.use_item(index, &mut events)?;
+            }
+            (Phase::Rolled { roll }, Command::Move) => {
+                let steps = roll.total().max(1) as u32;
+                self.advance(steps, &[], &mut events)?;
+            }
+            (Phase::Choose { remaining, .. }, Command::Choose(choice)) => {
+                let remaining = *remaining;
+                self.advance(remaining, &[choice], &mut events)?;
+            }
+            (Phase::Shop { tile }, Command::Buy(entry)) => {
+                let tile = *tile;
+                self.buy(tile, entry, &mut events)?;
+            }
+            (Phase::Shop { .. }, Command::SellItem(index)) => {
+                self.sell_item(index, &mut events)?;
+            }
+            (Phase::Shop { .. }, Command::SellRune(rune)) => {
+                self.sell_rune(rune, &mut events)?;
+            }
+            (Phase::Shop { .. }, Command::LeaveShop) => {
+                events.push(GameEvent::ShopClosed { seat: self.current });
+                self.end_turn(&mut events);
+            }
+            (Phase::Battle { attacker, defender }, Command::Fight) => {
+                let (attacker, defender) = (*attacker, *defender);
+                self.fight(attacker, defender, &mut events);
+                self.end_turn(&mut events);
+            }
+            (Phase::Battle { attacker, defender }, Command::Flee) => {
+                let (attacker, defender) = (*attacker, *defender);
+                self.flee(attacker, defender, &mut events);
+                self.end_turn(&mut events);
+            }
+            (Phase::Over { .. }, _) => return Err(RuleError::MatchOver),
+            _ => return Err(RuleError::NotNow),
+        }
+        if !matches!(self.phase, Phase::Over { .. }) {
+            self.check_victory(&mut events);
+        }
+        Ok(events)
+    }
+
+    pub fn shop_at(&self, tile: TileId) -> Option<(&Shop, &ShopDef)> {
+        let shop = self.shops.get(&tile)?;
+        Some((shop, self.data.shops.shops.get(shop.def)?))
+    }
+
+    // what an entry of a town shop costs, with the town markup
+    pub fn shop_price(&self, tile: TileId, entry: usize) -> Option<u32> {
+        let (_, def) = self.shop_at(tile)?;
+        let entry = def.stock.get(entry)?;
+        let base = entry.price.or_else(|| {
+            self.data
+                .shops
+                .base_price(&entry.goods, &self.data.items, &self.data.magic)
+        })?;
+        Some(base * def.markup / 100)
+    }
+
+    pub fn sell_price(&self, goods: &Goods) -> u32 {
+        self.data
+            .shops
+            .sell_price(goods, &self.data.items, &self.data.magic)
+    }
+
+    fn buy(
+        &mut self,
+        tile: TileId,
+        entry: usize,
+        events: &mut Vec<GameEvent>,
+    ) -> Result<(), RuleError> {
+        let seat = self.current;
+        let (shop, def) = self.shop_at(tile).ok_or(RuleError::NotNow)?;
+        let goods = def
+            .stock
+            .get(entry)
+            .ok_or(RuleError::NoStock(entry))?
+            .goods
+            .clone();
+        if shop.quantities[entry] == 0 {
+            return Err(RuleError::SoldOut(entry));
+        }
+        let price = self
+            .shop_price(tile, entry)
+            .ok_or(RuleError::NoStock(entry))?;
+        let player = &mut self.players[seat];
+        if player.gold < price {
+            return Err(RuleError::NotEnoughGold(price));
+        }
+        match &goods {
+            Goods::Item(item) => {
+                if player.inventory.len() >= self.data.items.inventory_slots {
+                    return Err(RuleError::InventoryFull);
+                }
+                player.inventory.push(item.clone());
+            }
+            Goods::Rune(rune) => player.add_rune(rune),
+            Goods::Upgrade(upgrade) => player.upgrade(*upgrade),
+        }
+        player.gold -= price;
+        if let Some(shop) = self.shops.get_mut(&tile) {
+            shop.quantities[entry] -= 1;
+        }
+        events.push(GameEvent::Bought { seat, goods, price });
+        Ok(())
+    }
+
+    fn sell_item(&mut self, index: usize, events: &mut Vec<GameEvent>) -> Result<(), RuleError> {
+        let seat = self.current;
+        let item = self.inventory_item(index)?.id.clone();
+        let goods = Goods::Item(item);
+        let price = self.sell_price(&goods);
+        let player = &mut self.players[seat];
+        player.inventory.remove(index);
+        player.gold += price;
+        events.push(GameEvent::Sold { seat, goods, price });
+        Ok(())
+    }
+
+    fn sell_rune(&mut self, rune: String, events: &mut Vec<GameEvent>) -> Result<(), RuleError> {
+        let seat = self.current;
+        let player = &mut self.players[seat];
+        if !player.take_rune(&rune) {
+            return Err(RuleError::NoRune(rune));
+        }
+        let goods = Goods::Rune(rune);
+        let price = self.sell_price(&goods);
+        self.players[seat].gold += price;
+        events.push(GameEvent::Sold { seat, goods, price });
+        Ok(())
+    }
+
+    fn inventory_item(&self, index: usize) -> Result<&ItemDef, RuleError> {
+        let id = self.players[self.current]
+            .inventory
+            .get(index)
+            .ok_or(RuleError::NoItem(index))?;
+        self.data
+            .items
+            .item(id)
+            .ok_or_else(|| RuleError::UnknownItem(id.clone()))
+    }
+
+    fn equip(&mut self, index: usize, events: &mut Vec<GameEvent>) -> Result<(), RuleError> {
+        let seat = self.current;
+        let item = self.inventory_item(index)?.clone();
+        let slot = item
+            .slot
+            .ok_or_else(|| RuleError::NotEquipment(item.id.clone()))?;
+        if let Some(old) = self.players[seat].equip(index, &item) {
+            events.push(GameEvent::Unequipped {
+                seat,
+                item: old,
+                slot,
+            });
+        }
+        events.push(GameEvent::Equipped {
+            seat,
+            item: item.id,
+            slot,
+        });
+        Ok(())
+    }
+
+    fn unequip(&mut self, slot: Slot, events: &mut Vec<GameEvent>) -> Result<(), RuleError> {
+        let seat = self.current;
+        let player = &mut self.players[seat];
+        if player.inventory.len() >= self.data.items.inventory_slots {
+            return Err(RuleError::InventoryFull);
+        }
+        let item = player
+            .equipment
+            .remove(&slot)
+            .ok_or(RuleError::EmptySlot(slot))?;
+        player.inventory.push(item.clone());
+        events.push(GameEvent::Unequipped { seat, item, slot });
+        Ok(())
+    }
+
+    fn use_item(&mut self, index: usize, events: &mut Vec<GameEvent>) -> Result<(), RuleError> {
+        let seat = self.current;
+        let item = self.inventory_item(index)?.clone();
+        if item.heal == 0 {
+            return Err(RuleError::NotUsable(item.id));
+        }
+        let player = &mut self.players[seat];
+        player.inventory.remove(index);
+        let healed = player.heal(item.heal);
+        events.push(GameEvent::ItemUsed {
+            seat,
+            item: item.id,
+        });
+        events.push(GameEvent::Healed {
+            seat,
+            health: healed,
+        });
+        Ok(())
+    }
+
+    fn cast(
+        &mut self,
+        id: &str,
+        target: Option<usize>,
+        events: &mut Vec<GameEvent>,
+    ) -> Result<(), RuleError> {
+        let seat = self.current;
+        let spell = self
+            .data
+            .magic
+            .spell(id)
+            .cloned()
+            .ok_or_else(|| RuleError::UnknownSpell(id.into()))?;
+        if !self.players[seat].knows(id) {
+            return Err(RuleError::SpellNotKnown(id.into()));
+        }
+        if !self
+            .castable_spells()
+            .iter()
+            .any(|castable| castable.id == id)
+        {
+            return Err(if self.players[seat].can_afford(&spell) {
+                RuleError::NotNow
+            } else {
+                RuleError::NotEnoughRunes(id.into())
+            });
+        }
+        let target = if spell.effect.needs_target() {
+            match target {
+                Some(target) if target != seat && target < self.players.len() => Some(target),
+                _ => return Err(RuleError::InvalidTarget(target)),
+            }
+        } else {
+            None
+        };
+
+        self.players[seat].pay(&spell);
+        events.push(GameEvent::SpellCast {
+            seat,
+            spell: spell.id.clone(),
+            target,
+        });
+
+        match (spell.effect, &mut self.phase) {
+            (SpellEffect::Reroll, Phase::Rolled { roll }) => {
+                let bonus = roll.bonus;
+                *roll = MOVEMENT_DICE.with_bonus(bonus).roll(&mut self.rng);
+                events.push(GameEvent::RollChanged {
+                    seat,
+                    roll: roll.clone(),
+                });
+            }
+            (SpellEffect::AddPips(pips), Phase::Rolled { roll }) => {
+                roll.bonus += pips;
+                events.push(GameEvent::RollChanged {
+                    seat,
+                    roll: roll.clone(),
+                });
+            }
+            (SpellEffect::ForceFace(face), Phase::Rolled { roll }) => {
+                if let Some(lowest) = roll.faces.iter_mut().min() {
+                    *lowest = face.min(MOVEMENT_DICE.sides);
+                }
+                events.push(GameEvent::RollChanged {
+                    seat,
+                    roll: roll.clone(),
+                });
+            }
+            (SpellEffect::Teleport(kind), _) => self.teleport(seat, kind, events),
+            (SpellEffect::Hinder(steps), _) => {
+                if let Some(target) = target {
+                    self.players[target].hindered += steps;
+                    events.push(GameEvent::Hindered {
+                        seat: target,
+                        steps,
+                    });
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn teleport(&mut self, seat: usize, kind: TileKind, events: &mut Vec<GameEvent>) {
+        if let Some(tile) = self.nearest(self.players[seat].tile, kind) {
+            self.players[seat].tile = tile;
+            events.push(GameEvent::Teleported { seat, tile });
+        }
+    }
+
+    // a defeated player goes back to the start to heal, unless it ran out of lives
+    fn defeat_player(&mut self, seat: usize, events: &mut Vec<GameEvent>) {
+        let start = self.board.start();
+        let player = &mut self.players[seat];
+        player.record.defeats += 1;
+        if self.rules.victory == Victory::LastStanding && player.record.defeats >= self.rules.lives
+        {
+            player.out = true;
+            events.push(GameEvent::Eliminated { seat });
+        }
+        player.health = player.stats.max_health;
+        player.tile = start;
+        events.push(GameEvent::Teleported { seat, tile: start });
+    }
+
+    // draws from the deck, shuffling the discarded cards back when it runs out
+    fn draw_card(&mut self, events: &mut Vec<GameEvent>) {
+        if self.deck.is_empty() {
+            self.deck.append(&mut self.discard);
+            self.deck.shuffle(&mut self.rng);
+        }
+        let Some(id) = self.deck.pop() else {
+            return;
+        };
+        self.discard.push(id.clone());
+        let Some(card) = self.data.cards.card(&id).cloned() else {
+            return;
+        };
+        let seat = self.current;
+        self.players[seat].record.cards_drawn += 1;
+        events.push(GameEvent::CardDrawn { seat, card: id });
+        for step in &card.effects {
+            if step.effect.is_board_effect() {
+                self.apply_board_effect(&step.effect, events);
+                continue;
+            }
+            let targets = (0..self.players.len())
+                .filter(|target| !self.players[*target].out)
+                .filter(|target| match step.target {
+                    CardTarget::Drawer => *target == seat,
+                    CardTarget::Others => *target != seat,
+                    CardTarget::Everyone => true,
+                })
+                .collect::<Vec<_>>();
+            for target in targets {
+                self.apply_card_effect(target, &step.effect, events);
+            }
+        }
+    }
+
+    fn apply_card_effect(&mut self, seat: usize, effect: &CardEffect, events: &mut Vec<GameEvent>) {
+        let player = &mut self.players[seat];
+        match effect {
+            CardEffect::Heal(health) => {
+                let health = player.heal(*health);
+                events.push(GameEvent::Healed { seat, health });
+            }
+            CardEffect::Damage(damage) => {
+                let damage = (*damage).min(player.health);
+                player.health -= damage;
+                events.push(GameEvent::Damaged { seat, damage });
+                if player.health == 0 {
+                    let name = player.name.clone();
+                    events.push(GameEvent::Defeated {
+                        fighter: Fighter::Player(seat),
+                        name,
+                    });
+                    self.defeat_player(seat, events);
+                }
+            }
+            CardEffect::Gold(amount) => {
+                let gold = (player.gold as i64 + *amount as i64).max(0) as u32;
+                let amount = gold as i32 - player.gold as i32;
+                player.gold = gold;
+                events.push(GameEvent::GoldChanged { seat, amount });
+            }
+            CardEffect::Item(item) if player.inventory.len() < self.data.items.inventory_slots => {
+                player.inventory.push(item.clone());
+                events.push(GameEvent::Looted {
+                    seat,
+                    gold: 0,
+                    items: vec![item.clone()],
+                });
+            }
+            // there is no swapping on a card, the item is left behind
+            CardEffect::Item(item) => events.push(GameEvent::InventoryFull {
+                seat,
+                item: item.clone(),
+            }),
+            CardEffect::Rune(rune) => {
+                let rune = match rune {
+                    Some(rune) => Some(rune.clone()),
+                    None => self
+                        .data
+                        .magic
+                        .random_rune(&mut self.rng)
+                        .map(|rune| rune.id.clone()),
+                };
+                if let Some(rune) = rune {
+                    self.players[seat].add_rune(&rune);
+                    events.push(GameEvent::RuneCollected { seat, rune });
+                }
+            }
+            CardEffect::Teleport(kind) => self.teleport(seat, *kind, events),
+            CardEffect::Hinder(steps) => {
+                player.hindered += steps;
+                events.push(GameEvent::Hindered {
+                    seat,
+                    steps: *steps,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_board_effect(&mut self, effect: &CardEffect, events: &mut Vec<GameEvent>) {
+        match effect {
+            CardEffect::SpawnMonster => self.spawn_monster(events),
+            CardEffect::Restock => {
+                for shop in self.shops.values_mut() {
+                    if let Some(def) = self.data.shops.shops.get(shop.def) {
+                        shop.restock(def);
+                    }
+                }
+                events.push(GameEvent::ShopsRestocked);
+            }
+            CardEffect::Weather {
+                name,
+                rounds,
+                movement,
+            } => {
+                let weather = Weather {
+                    name: name.clone(),
+                    rounds: *rounds,
+                    movement: *movement,
+                };
+                self.weather = Some(weather.clone());
+                events.push(GameEvent::WeatherChanged {
+                    weather: Some(weather),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // the closest tile of a kind going forward, ignoring the one we are on
+    fn nearest(&self, from: TileId, kind: TileKind) -> Option<TileId> {
+        let distances = self.board.distances_from(from);
+        self.board
+            .tiles_of(kind)
+            .filter(|tile| tile.id != from)
+            .filter_map(|tile| distances[tile.id.0].map(|distance| (distance, tile.id)))
+            .min()
+            .map(|(_, id)| id)
+    }
+
+    fn advance(
+        &mut self,
+        steps: u32,
+        choices: &[TileId],
+        events: &mut Vec<GameEvent>,
+    ) -> Result<(), RuleError> {
+        let seat = self.current;
+        let walk = walk(&self.board, self.players[seat].tile, steps, choices)?;
+        if let Some(last) = walk.path().last() {
+            self.players[seat].tile = *last;
+            self.players[seat].record.steps += walk.path().len() as u32;
+            events.push(GameEvent::Moved {
+                seat,
+                path: walk.path().to_vec(),
+            });
+        }
+        match walk {
+            Walk::Arrived { .. } => {
+                self.land(events);
+                if !matches!(self.phase, Phase::Battle { .. } | Phase::Shop { .. }) {
+                    self.end_turn(events);
+                }
+            }
+            Walk::Fork {
+                remaining, options, ..
+            } => {
+                events.push(GameEvent::ForkReached {
+                    seat,
+                    tile: self.players[seat].tile,
+                    options: options.clone(),
+                });
+                self.phase = Phase::Choose { remaining, options };
+            }
+        }
+        Ok(())
+    }
+
+    fn land(&mut self, events: &mut Vec<GameEvent>) {
+        let seat = self.current;
+        let tile = self.board.tile(self.players[seat].tile);
+        let kind = tile.kind;
+        events.push(GameEvent::Landed {
+            seat,
+            tile: tile.id,
+            kind,
+        });
+        match kind {
+            TileKind::Rune => {
+                if let Some(rune) = self.data.magic.random_rune(&mut self.rng) {
+                    self.players[seat].add_rune(&rune.id);
+                    events.push(GameEvent::RuneCollected {
+                        seat,
+                        rune: rune.id.clone(),
+                    });
+                }
+            }
+            TileKind::Event => self.draw_card(events),
+            TileKind::Shrine => {
+                let player = &self.players[seat];
+                let unknown = self
+                    .data
+                    .magic
+                    .spells
+                    .iter()
+                    .filter(|spell| !player.knows(&spell.id))
+                    .map(|spell| spell.id.clone())
+                    .collect::<Vec<_>>();
+                if !unknown.is_empty() {
+                    let spell = unknown[self.rng.gen_range(0..unknown.len())].clone();
+                    self.players[seat].spellbook.push(spell.clone());
+                    events.push(GameEvent::SpellLearned { seat, spell });
+                }
+            }
+            _ => {}
+        }
+
+        // landing on a monster or where another player stands starts a battle, except at
+        // the start, otherwise towns open their shop
+        let tile = self.players[seat].tile;
+        if tile == self.board.start() {
+            return;
+        }
+        if let Some(defender) = self.defender_at(seat, tile) {
+            let attacker = Fighter::Player(seat);
+            events.push(GameEvent::BattleStarted { attacker, defender });
+            self.phase = Phase::Battle { attacker, defender };
+        } else if kind == TileKind::Town && self.shops.contains_key(&tile) {
+            events.push(GameEvent::ShopOpened { seat, tile });
+            self.phase = Phase::Shop { tile };
+        }
+    }
+
+    // who the player fights on landing at the tile, a monster before another player
+    pub fn defender_at(&self, seat: usize, tile: TileId) -> Option<Fighter> {
+        self.monsters
+            .iter()
+            .find(|monster| monster.tile == tile)
+            .map(|monster| Fighter::Monster(monster.id))
+            .or_else(|| {
+                self.players
+                    .iter()
+                    .find(|other| other.seat != seat && !other.out && other.tile == tile)
+                    .map(|other| Fighter::Player(other.seat))
+            })
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        match self.phase {
+            Phase::Over { winner } => Some(winner),
+            _ => None,
+        }
+    }
+
+    // the players ranked by the score of the victory condition, the winner always first
+    pub fn standings(&self) -> Vec<Standing> {
+        let winner = self.winner();
+        let mut standings = self
+            .players
+            .iter()
+            .map(|player| Standing {
+                seat: player.seat,
+                name: player.name.clone(),
+                score: self.score(player),
+                gold: player.gold,
+                out: player.out,
+                record: player.record,
+            })
+            .collect::<Vec<_>>();
+        standings.sort_by_key(|standing| {
+            (
+                Reverse(winner == Some(standing.seat)),
+                standing.out,
+                Reverse(standing.score),
+                Reverse(standing.gold),
+                standing.seat,
+            )
+        });
+        standings
+    }
+
+    pub fn score(&self, player: &Player) -> u32 {
+        match self.rules.victory {
+            Victory::KeyRunes => self
+                .data
+                .magic
+                .runes
+                .iter()
+                .filter(|rune| rune.key && player.runes.contains_key(&rune.id))
+                .count() as u32,
+            Victory::Gold => player.gold,
+            Victory::LastStanding => self.rules.lives.saturating_sub(player.record.defeats),
+            // what everything the player owns would sell for
+            Victory::TurnLimit => {
+                let items = player
+                    .inventory
+                    .iter()
+                    .chain(player.equipment.values())
+                    .map(|item| self.sell_price(&Goods::Item(item.clone())))
+                    .sum::<u32>();
+                let runes = player
+                    .runes
+                    .iter()
+                    .map(|(rune, count)| self.sell_price(&Goods::Rune(rune.clone())) * count)
+                    .sum::<u32>();
+                player.gold + items + runes
+            }
+        }
+    }
+
+    // checks the players from the current one, so who triggers the win gets it on a tie
+    fn check_victory(&mut self, events: &mut Vec<GameEvent>) -> bool {
+        let count = self.players.len();
+        let mut seats = (0..count).map(|offset| (self.current + offset) % count);
+        let everyone_out = self.players.iter().all(|player| player.out);
+        let winner = match self.rules.victory {
+            // a card can take everyone left out at once, then the standings decide
+            _ if everyone_out => self.standings().first().map(|standing| standing.seat),
+            Victory::KeyRunes => {
+                let keys = self.data.magic.runes.iter().filter(|rune| rune.key).count() as u32;
+                seats.find(|seat| keys > 0 && self.score(&self.players[*seat]) == keys)
+            }
+            Victory::Gold => seats.find(|seat| self.players[*seat].gold >= self.rules.gold_target),
+            Victory::LastStanding => {
+                let standing = self
+                    .players
+                    .iter()
+                    .filter(|player| !player.out)
+                    .collect::<Vec<_>>();
+                match standing.as_slice() {
+                    [last] if count > 1 => Some(last.seat),
+                    _ => None,
+                }
+            }
+            Victory::TurnLimit => (self.turn > self.rules.turn_limit)
+                .then(|| self.standings().first().map(|standing| standing.seat))
+                .flatten(),
+        };
+        let Some(winner) = winner else {
+            return false;
+        };
+        self.phase = Phase::Over { winner };
+        events.push(GameEvent::MatchEnded { winner });
+        true
+    }
+
+    pub fn combatant(&self, fighter: Fighter) -> Combatant {
+        match fighter {
+            Fighter::Player(seat) => {
+                let player = &self.players[seat];
+                let equipment = player.equipment.values().filter_map(|item| {
+                    let item = self.data.items.item(item)?;
+                    Some(Modifier {
+                        source: item.name.clone(),
+                        attack: item.attack,
+                        defense: item.defense,
+                        attack_dice: item.attack_dice,
+                        defense_dice: item.defense_dice,
+                    })
+                });
+                let runes = player.runes.iter().filter_map(|(rune, count)| {
+                    let rune = self.data.magic.rune(rune)?;
+                    let count = *count as i32;
+                    (rune.attack != 0 || rune.defense != 0).then(|| Modifier {
+                        source: format!("{} x{}", rune.name, count),
+                        attack: rune.attack * count,
+                        defense: rune.defense * count,
+                        ..Default::default()
+                    })
+                });
+                Combatant {
+                    fighter,
+                    name: player.name.clone(),
+                    health: player.health,
+                    max_health: player.stats.max_health,
+                    attack: player.stats.attack,
+                    defense: player.stats.defense,
+                    modifiers: equipment.chain(runes).collect(),
+                    first_strike: false,
+                }
+            }
+            Fighter::Monster(id) => {
+                let monster = self.monster(id).expect("monster should be on the board");
+                let def = self
+                    .data
+                    .monsters
+                    .monster(&monster.kind)
+                    .expect("monster should be defined");
+                monster.combatant(def)
+            }
+        }
+    }
+
+    // running away costs some health but never the last point, so it can't defeat anyone
+    fn flee(&mut self, attacker: Fighter, defender: Fighter, events: &mut Vec<GameEvent>) {
+        let Fighter::Player(seat) = attacker else {
+            return;
+        };
+        let player = &mut self.players[seat];
+        let damage = self
+            .data
+            .battle
+            .flee_damage
+            .min(player.health.saturating_sub(1));
+        player.health -= damage;
+        events.push(GameEvent::Fled {
+            seat,
+            from: defender,
+            damage,
+        });
+    }
+
+    fn fight(&mut self, attacker: Fighter, defender: Fighter, events: &mut Vec<GameEvent>) {
+        let report = resolve(
+            self.combatant(attacker),
+            self.combatant(defender),
+            &mut self.rng,
+        );
+        for exchange in &report.exchanges {
+            match exchange.target {
+                Fighter::Player(seat) => self.players[seat].health = exchange.remaining,
+                Fighter::Monster(id) => {
+                    if let Some(monster) = self.monsters.iter_mut().find(|monster| monster.id == id)
+                    {
+                        monster.health = exchange.remaining;
+                    }
+                }
+            }
+        }
+        let Some(fighter) = report.defeated else {
+            events.push(GameEvent::BattleResolved(Box::new(report)));
+            return;
+        };
+        let name = report.combatant(fighter).name.clone();
+        events.push(GameEvent::BattleResolved(Box::new(report)));
+        events.push(GameEvent::Defeated { fighter, name });
+        let winner = if attacker == fighter {
+            defender
+        } else {
+            attacker
+        };
+        if let Fighter::Player(seat) = winner {
+            let record = &mut self.players[seat].record;
+            record.battles_won += 1;
+            if let Fighter::Monster(_) = fighter {
+                record.monsters_defeated += 1;
+            }
+        }
+
+        match fighter {
+            Fighter::Player(seat) => self.defeat_player(seat, events),
+            // a defeated monster leaves its gold and loot to the player that beat it
+            Fighter::Monster(id) => {
+                let Some(index) = self.monsters.iter().position(|monster| monster.id == id) else {
+                    return;
+                };
+                let monster = self.monsters.remove(index);
+                if let (Fighter::Player(seat), Some(def)) =
+                    (winner, self.data.monsters.monster(&monster.kind))
+                {
+                    let mut items = def.roll_loot(&mut self.rng);
+                    let player = &mut self.players[seat];
+                    let room = self
+                        .data
+                        .items
+                        .inventory_slots
+                        .saturating_sub(player.inventory.len());
+                    let left = items.split_off(room.min(items.len()));
+                    player.gold += def.gold;
+                    player.inventory.extend(items.iter().cloned());
+                    events.push(GameEvent::Looted {
+                        seat,
+                        gold: def.gold,
+                        items,
+                    });
+                    events.extend(
+                        left.into_iter()
+                            .map(|item| GameEvent::InventoryFull { seat, item }),
+                    );
+                }
+            }
+        }
+    }
+
+    fn spawn_monster(&mut self, events: &mut Vec<GameEvent>) {
+        let free = self
+            .board
+            .tiles_of(TileKind::Battle)
+            .map(|tile| tile.id)
+            .filter(|tile| !self.is_occupied(*tile))
+            .collect::<Vec<_>>();
+        let Some(tile) = free.choose(&mut self.rng).copied() else {
+            return;
+        };
+        let Some(def) = self.data.monsters.random_monster(&mut self.rng) else {
+            return;
+        };
+        let monster = Monster {
+            id: self.next_monster,
+            kind: def.id.clone(),
+            tile,
+            health: def.health,
+        };
+        self.next_monster += 1;
+        events.push(GameEvent::MonsterSpawned {
+            monster: monster.id,
+            kind: monster.kind.clone(),
+            tile,
+        });
+        self.monsters.push(monster);
+    }
+
+    fn is_occupied(&self, tile: TileId) -> bool {
+        self.monsters.iter().any(|monster| monster.tile == tile)
+            || self.players.iter().any(|player| player.tile == tile)
+    }
+
+    // after everyone played monsters heal, roamers wander a tile and new ones may appear
+    fn end_round(&mut self, events: &mut Vec<GameEvent>) {
+        if let Some(weather) = &mut self.weather {
+            weather.rounds = weather.rounds.saturating_sub(1);
+            if weather.rounds == 0 {
+                self.weather = None;
+                events.push(GameEvent::WeatherChanged { weather: None });
+            }
+        }
+
+        for index in 0..self.monsters.len() {
+            let Some(def) = self.data.monsters.monster(&self.monsters[index].kind) else {
+                continue;
+            };
+            let monster = &mut self.monsters[index];
+            monster.health = (monster.health + def.regenerate()).min(def.health);
+            if def.behaviour != Behaviour::Roam {
+                continue;
+            }
+
+            let start = self.board.start();
+            let options = self
+                .board
+                .next(self.monsters[index].tile)
+                .iter()
+                .copied()
+                .filter(|tile| {
+                    *tile != start && !self.monsters.iter().any(|other| other.tile == *tile)
+                })
+                .collect::<Vec<_>>();
+            if let Some(tile) = options.choose(&mut self.rng).copied() {
+                let monster = &mut self.monsters[index];
+                monster.tile = tile;
+                events.push(GameEvent::MonsterMoved {
+                    monster: monster.id,
+                    tile,
+                });
+            }
+        }
+
+        for shop in self.shops.values_mut() {
+            if let Some(def) = self.data.shops.shops.get(shop.def) {
+                if def.restock_rounds > 0 && (self.turn - 1).is_multiple_of(def.restock_rounds) {
+                    shop.restock(def);
+                }
+            }
+        }
+
+        let spawn = &self.data.monsters.spawn;
+        if spawn.every_rounds > 0
+            && (self.turn - 1).is_multiple_of(spawn.every_rounds)
+            && (self.monsters.len() as u32) < spawn.max
+        {
+            self.spawn_monster(events);
+        }
+    }
+
+    // eliminated players are skipped, the match may end before the next turn starts
+    fn end_turn(&mut self, events: &mut Vec<GameEvent>) {
+        if self.players.iter().all(|player| player.out) {
+            self.check_victory(events);
+            return;
+        }
+        loop {
+            self.current = (self.current + 1) % self.players.len();
+            if self.current == 0 {
+                self.turn += 1;
+                self.end_round(events);
+            }
+            if !self.players[self.current].out {
+                break;
+            }
+        }
+        if self.check_victory(events) {
+            return;
+        }
+        self.phase = Phase::Roll;
+        events.push(GameEvent::TurnStarted {
+            seat: self.current,
+            turn: self.turn,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::Tile,
+        rules::{
+            cards::{CardDef, CardKind, CardStep},
+            shop::StockEntry,
+        },
+    };
+
+    // a loop longer than any roll so every move ends on an event
+    fn board() -> Board {
+        let length = MOVEMENT_DICE.max() as usize + 1;
+        Board {
+            name: "Test".into(),
+            tiles: (0..length)
+                .map(|index| Tile {
+                    id: TileId(index),
+                    kind: if index == 0 {
+                        TileKind::Start
+                    } else {
+                        TileKind::Event
+                    },
+                    position: (index as f32, 0.),
+                    next: vec![TileId((index + 1) % length)],
+                })
+                .collect(),
+        }
+    }
+
+    fn card(target: CardTarget, effect: CardEffect) -> GameData {
+        let mut data = GameData::default();
+        data.cards.cards.push(CardDef {
+            id: "card".into(),
+            name: "Card".into(),
+            kind: CardKind::Trap,
+            text: String::new(),
+            effects: vec![CardStep { target, effect }],
+            copies: 1,
+        });
+        data
+    }
+
+    fn state(data: GameData, rules: MatchRules, players: usize) -> MatchState {
+        let names = (0..players)
+            .map(|seat| format!("Player {}", seat + 1))
+            .collect::<Vec<_>>();
+        MatchState::new(board(), Arc::new(data), rules, &names, 3)
+    }
+
+    fn last_standing(data: GameData, players: usize) -> MatchState {
+        let rules = MatchRules {
+            victory: Victory::LastStanding,
+            lives: 1,
+            ..MatchRules::default()
+        };
+        state(data, rules, players)
+    }
+
+    fn roll_and_move(state: &mut MatchState) -> Vec<GameEvent> {
+        state.apply(Command::Roll).unwrap();
+        state.apply(Command::Move).unwrap()
+    }
+
+    #[test]
+    fn everyone_out_still_ends_the_match() {
+        let data = card(CardTarget::Everyone, CardEffect::Damage(1000));
+        let mut state = last_standing(data, 3);
+        let events = roll_and_move(&mut state);
+        assert!(state.players.iter().all(|player| player.out));
+        let winner = state.standings()[0].seat;
+        assert_eq!(state.phase, Phase::Over { winner });
+        assert!(events.contains(&GameEvent::MatchEnded { winner }));
+        assert_eq!(state.apply(Command::Roll), Err(RuleError::MatchOver));
+    }
+
+    #[test]
+    fn last_one_standing_wins() {
+        let data = card(CardTarget::Others, CardEffect::Damage(1000));
+        let mut state = last_standing(data, 3);
+        let events = roll_and_move(&mut state);
+        assert_eq!(state.phase, Phase::Over { winner: 0 });
+        assert!(events.contains(&GameEvent::MatchEnded { winner: 0 }));
+    }
+
+    fn battle(state: &mut MatchState) {
+        state.phase = Phase::Battle {
+            attacker: Fighter::Player(0),
+            defender: Fighter::Player(1),
+        };
+    }
+
+    #[test]
+    fn fleeing_costs_health_and_ends_the_turn() {
+        let mut state = state(GameData::default(), MatchRules::default(), 2);
+        battle(&mut state);
+        let health = state.players[0].health;
+        let damage = state.data.battle.flee_damage;
+        let events = state.apply(Command::Flee).unwrap();
+        assert_eq!(state.players[0].health, health - damage);
+        assert_eq!(state.players[1].health, health);
+        assert!(events.contains(&GameEvent::Fled {
+            seat: 0,
+            from: Fighter::Player(1),
+            damage
+        }));
+        assert_eq!(state.current, 1);
+        assert_eq!(state.phase, Phase::Roll);
+    }
+
+    #[test]
+    fn fleeing_never_defeats() {
+        let mut state = state(GameData::default(), MatchRules::default(), 2);
+        battle(&mut state);
+        state.players[0].health = 1;
+        state.apply(Command::Flee).unwrap();
+        assert_eq!(state.players[0].health, 1);
+        assert_eq!(state.players[0].record.defeats, 0);
+    }
+
+    #[test]
+    fn fleeing_only_from_battles() {
+        let mut state = state(GameData::default(), MatchRules::default(), 2);
+        assert_eq!(state.apply(Command::Flee), Err(RuleError::NotNow));
+    }
+
+    fn potion() -> ItemDef {
+        ItemDef {
+            id: "potion".into(),
+            name: "Potion".into(),
+            description: String::new(),
+            slot: None,
+            attack: 0,
+            defense: 0,
+            attack_dice: 0,
+            defense_dice: 0,
+            heal: 3,
+            price: 10,
+        }
+    }
+
+    // the current player in a shop selling a single potion
+    fn shopping(gold: u32) -> MatchState {
+        let mut data = GameData::default();
+        data.items.items.push(potion());
+        let def = ShopDef {
+            name: "Market".into(),
+            stock: vec![StockEntry {
+                goods: Goods::Item("potion".into()),
+                price: None,
+                quantity: 1,
+            }],
+            markup: 100,
+            restock_rounds: 0,
+        };
+        data.shops.shops.push(def.clone());
+        let mut state = state(data, MatchRules::default(), 2);
+        state.shops.insert(TileId(1), Shop::new(0, &def));
+        state.phase = Phase::Shop { tile: TileId(1) };
+        state.players[0].gold = gold;
+        state
+    }
+
+    #[test]
+    fn buying_takes_the_gold_and_the_stock() {
+        let mut state = shopping(15);
+        let events = state.apply(Command::Buy(0)).unwrap();
+        assert_eq!(
+            events,
+            vec![GameEvent::Bought {
+                seat: 0,
+                goods: Goods::Item("potion".into()),
+                price: 10
+            }]
+        );
+        assert_eq!(state.players[0].gold, 5);
+        assert_eq!(state.players[0].inventory, vec!["potion".to_string()]);
+        assert_eq!(state.shops[&TileId(1)].quantities, vec![0]);
+        assert_eq!(state.apply(Command::Buy(0)), Err(RuleError::SoldOut(0)));
+    }
+
+    #[test]
+    fn buying_without_the_gold_changes_nothing() {
+        let mut state = shopping(9);
+        let before = state.clone();
+        assert_eq!(
+            state.apply(Command::Buy(0)),
+            Err(RuleError::NotEnoughGold(10))
+        );
+        assert_eq!(state, before);
+    }
+
+    #[test]
+    fn card_effects_reach_their_targets() {
+        let cases = [
+            (CardTarget::Drawer, CardEffect::Gold(5), [15, 10]),
+            (CardTarget::Others, CardEffect::Gold(5), [10, 15]),
+            (CardTarget::Everyone, CardEffect::Gold(5), [15, 15]),
+            // gold never goes below nothing
+            (CardTarget::Drawer, CardEffect::Gold(-50), [0, 10]),
+        ];
+        for (target, effect, gold) in cases {
+            let mut state = state(card(target, effect.clone()), MatchRules::default(), 2);
+            for player in &mut state.players {
+                player.gold = 10;
+            }
+            roll_and_move(&mut state);
+            let after = state.players.iter().map(|player| player.gold);
+            assert_eq!(after.collect::<Vec<_>>(), gold, "{:?} {:?}", target, effect);
+        }
+    }
+
+    #[test]
+    fn card_effects_heal_and_hinder() {
+        let mut healed = state(
+            card(CardTarget::Drawer, CardEffect::Heal(2)),
+            MatchRules::default(),
+            2,
+        );
+        healed.players[0].health = 1;
+        let events = roll_and_move(&mut healed);
+        assert_eq!(healed.players[0].health, 3);
+        assert!(events.contains(&GameEvent::Healed { seat: 0, health: 2 }));
+
+        let mut hindered = state(
+            card(CardTarget::Others, CardEffect::Hinder(2)),
+            MatchRules::default(),
+            2,
+        );
+        roll_and_move(&mut hindered);
+        assert_eq!(hindered.players[0].hindered, 0);
+        assert_eq!(hindered.players[1].hindered, 2);
+    }
+
+    #[test]
+    fn item_card_fills_the_inventory() {
+        let mut data = card(CardTarget::Drawer, CardEffect::Item("potion".into()));
+        data.items.items.push(potion());
+        let mut state = state(data, MatchRules::default(), 2);
+        let events = roll_and_move(&mut state);
+        assert_eq!(state.players[0].inventory, vec!["potion".to_string()]);
+        assert!(events.contains(&GameEvent::Looted {
+            seat: 0,
+            gold: 0,
+            items: vec!["potion".into()]
+        }));
+    }
+
+    // there is no swapping, the item is left behind and the player is told
+    #[test]
+    fn item_card_with_a_full_inventory_is_left_behind() {
+        let mut data = card(CardTarget::Drawer, CardEffect::Item("potion".into()));
+        data.items.items.push(potion());
+        let mut state = state(data, MatchRules::default(), 2);
+        let full = vec!["potion".to_string(); state.data.items.inventory_slots];
+        state.players[0].inventory = full.clone();
+        let events = roll_and_move(&mut state);
+        assert_eq!(state.players[0].inventory, full);
+        assert!(events.contains(&GameEvent::InventoryFull {
+            seat: 0,
+            item: "potion".into()
+        }));
+    }
+}
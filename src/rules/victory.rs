@@ -0,0 +1,86 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use serde::{Deserialize, Serialize};
+
+use super::Record;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default, Deserialize, Serialize)]
+pub enum Victory {
+    #[default]
+    KeyRunes,
+    Gold,
+    LastStanding,
+    TurnLimit,
+}
+
+impl Victory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Victory::KeyRunes => "Key Runes",
+            Victory::Gold => "Gold Target",
+            Victory::LastStanding => "Last Standing",
+            Victory::TurnLimit => "Turn Limit",
+        }
+    }
+
+    // what the standings are ranked by
+    pub fn score_name(&self) -> &'static str {
+        match self {
+            Victory::KeyRunes => "Key runes",
+            Victory::Gold => "Gold",
+            Victory::LastStanding => "Lives",
+            Victory::TurnLimit => "Wealth",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MatchRules {
+    pub victory: Victory,
+    pub gold_target: u32,
+    pub turn_limit: u32,
+    pub lives: u32,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            victory: Default::default(),
+            gold_target: 50,
+            turn_limit: 20,
+            lives: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Standing {
+    pub seat: usize,
+    pub name: String,
+    pub score: u32,
+    pub gold: u32,
+    pub out: bool,
+    pub record: Record,
+}
@@ -0,0 +1,474 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{board::Computers, clear_scene, confirm_pressed};
+use crate::{
+    dice::{percent, Roll},
+    game::{Announcement, Assets, Config, SafeArea, State, Theme},
+    rules::{
+        odds, BattleReport, Combatant, Command, Exchange, Fighter, GameEvent, History, MatchState,
+        Modifier, Replay,
+    },
+};
+
+const ROLL_SECONDS: f32 = 1.2;
+const WATCH_SECONDS: f32 = 2.5;
+const DIE_SIZE: f32 = 64.0;
+
+pub struct BattleScene;
+
+impl Plugin for BattleScene {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(State::Battle).with_system(setup))
+            .add_system_set(
+                SystemSet::on_update(State::Battle)
+                    .with_system(roll_dice)
+                    .with_system(reveal)
+                    .with_system(finish),
+            )
+            .add_system_set(
+                SystemSet::on_exit(State::Battle).with_system(clear_scene::<OnBattleScene>),
+            );
+    }
+}
+
+#[derive(Component)]
+struct OnBattleScene;
+
+// what happened in the battle, the board sends the events once it resumes
+#[derive(Resource)]
+pub struct BattleOutcome {
+    pub events: Vec<GameEvent>,
+}
+
+// when only computers fight nobody is there to close the battle, so it closes by itself
+#[derive(Resource)]
+struct Watching {
+    timer: Timer,
+}
+
+#[derive(Component)]
+struct RollingDie {
+    face: u8,
+    sides: u8,
+    timer: Timer,
+}
+
+#[derive(Component)]
+struct Reveal {
+    timer: Timer,
+    announcement: Option<String>,
+}
+
+use bevy::prelude::State as BevyState;
+#[allow(clippy::too_many_arguments)]
+fn setup(
+    mut commands: Commands,
+    mut state: ResMut<MatchState>,
+    assets: Res<Assets>,
+    theme: Res<Theme>,
+    config: Res<Config>,
+    computers: Res<Computers>,
+    mut history: ResMut<History>,
+    mut replay: ResMut<Replay>,
+    mut game_state: ResMut<BevyState<State>>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    let events = match history.apply(&mut state, Command::Fight) {
+        Ok(events) => events,
+        Err(err) => {
+            warn!("battle can't be fought: {}", err);
+            game_state.pop().expect("Failed to pop game state");
+            return;
+        }
+    };
+    let Some(report) = events.iter().find_map(|event| match event {
+        GameEvent::BattleResolved(report) => Some(report.as_ref().clone()),
+        _ => None,
+    }) else {
+        game_state.pop().expect("Failed to pop game state");
+        return;
+    };
+    replay.record(Command::Fight, &state);
+    commands.insert_resource(BattleOutcome { events });
+    let human = |combatant: &Combatant| match combatant.fighter {
+        Fighter::Player(seat) => !computers.plays(seat),
+        Fighter::Monster(_) => false,
+    };
+    if !human(&report.attacker) && !human(&report.defender) {
+        commands.insert_resource(Watching {
+            timer: Timer::from_seconds(WATCH_SECONDS, TimerMode::Once),
+        });
+    }
+
+    ev_announcement.send(Announcement::scene(format!(
+        "Battle, {} against {}",
+        report.attacker.name, report.defender.name
+    )));
+
+    let roll_seconds = if config.reduced_motion {
+        0.
+    } else {
+        ROLL_SECONDS
+    };
+    let text = |value: String, font_size: f32, color: Color| {
+        TextBundle::from_section(
+            value,
+            TextStyle {
+                font: assets.default_font.clone(),
+                font_size,
+                color,
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(8.0)),
+            ..default()
+        })
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: theme.panel_color.into(),
+                ..default()
+            },
+            SafeArea,
+            OnBattleScene,
+        ))
+        .with_children(|parent| {
+            parent.spawn(text(
+                "Battle!".into(),
+                theme.title_font_size,
+                theme.title_color,
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        justify_content: JustifyContent::SpaceAround,
+                        size: Size::new(Val::Percent(80.0), Val::Auto),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for combatant in [&report.attacker, &report.defender] {
+                        spawn_combatant(parent, combatant, &text, &theme, &config);
+                    }
+                });
+
+            if config.show_odds {
+                parent.spawn(text(
+                    describe_odds(&report.attacker, &report.defender),
+                    theme.button_font_size_small,
+                    theme.text_color,
+                ));
+            }
+
+            for (index, exchange) in report.exchanges.iter().enumerate() {
+                let seconds = roll_seconds * (index + 1) as f32;
+                spawn_exchange(
+                    parent, &report, exchange, seconds, &text, &assets, &theme, &config,
+                );
+            }
+
+            parent.spawn((
+                TextBundle {
+                    visibility: Visibility::INVISIBLE,
+                    ..text(
+                        "Press Space to continue".into(),
+                        theme.label_font_size,
+                        theme.title_color,
+                    )
+                },
+                Reveal {
+                    timer: Timer::from_seconds(
+                        roll_seconds * report.exchanges.len() as f32,
+                        TimerMode::Once,
+                    ),
+                    announcement: None,
+                },
+            ));
+        });
+}
+
+fn spawn_combatant(
+    parent: &mut ChildBuilder,
+    combatant: &Combatant,
+    text: &impl Fn(String, f32, Color) -> TextBundle,
+    theme: &Theme,
+    config: &Config,
+) {
+    let color = match combatant.fighter {
+        Fighter::Player(seat) => config.palette.player_color(seat),
+        Fighter::Monster(_) => theme.text_color,
+    };
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(text(combatant.name.clone(), theme.label_font_size, color));
+            parent.spawn(text(
+                format!("Health {}/{}", combatant.health, combatant.max_health),
+                theme.button_font_size_small,
+                theme.text_color,
+            ));
+            parent.spawn(text(
+                format!(
+                    "Attack {}, defence {}",
+                    combatant.attack_pool(),
+                    combatant.defense_pool()
+                ),
+                theme.button_font_size_small,
+                theme.text_color,
+            ));
+            for modifier in &combatant.modifiers {
+                parent.spawn(text(
+                    describe(modifier),
+                    theme.button_font_size_small,
+                    theme.text_color,
+                ));
+            }
+        });
+}
+
+// "Sword: +1 attack die" only listing what the modifier changes
+fn describe(modifier: &Modifier) -> String {
+    let changes = [
+        (modifier.attack_dice as i32, "attack die"),
+        (modifier.attack, "attack"),
+        (modifier.defense_dice as i32, "defence die"),
+        (modifier.defense, "defence"),
+    ]
+    .iter()
+    .filter(|(value, _)| *value != 0)
+    .map(|(value, what)| format!("{:+} {}", value, what))
+    .collect::<Vec<_>>();
+    format!("{}: {}", modifier.source, changes.join(", "))
+}
+
+// "Odds: Player 1 wins 42%, Goblin wins 9%, both stand 49%" worked out before the dice roll
+fn describe_odds(attacker: &Combatant, defender: &Combatant) -> String {
+    let odds = odds(attacker, defender);
+    format!(
+        "Odds: {} wins {}, {} wins {}, both stand {}",
+        attacker.name,
+        percent(odds.attacker_wins),
+        defender.name,
+        percent(odds.defender_wins),
+        percent(odds.both_stand)
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_exchange(
+    parent: &mut ChildBuilder,
+    report: &BattleReport,
+    exchange: &Exchange,
+    seconds: f32,
+    text: &impl Fn(String, f32, Color) -> TextBundle,
+    assets: &Assets,
+    theme: &Theme,
+    config: &Config,
+) {
+    let striker = report.combatant(exchange.striker);
+    let target = report.combatant(exchange.target);
+    let attack_sides = striker.attack.sides;
+    let defense_sides = target.defense.sides;
+
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                align_items: AlignItems::Center,
+                margin: UiRect::top(Val::Px(20.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(text(
+                format!("{} strikes", striker.name),
+                theme.button_font_size_small,
+                theme.text_color,
+            ));
+            for face in &exchange.attack.faces {
+                spawn_die(parent, *face, attack_sides, seconds, assets, config);
+            }
+            parent.spawn(text(
+                format!("{} blocks", target.name),
+                theme.button_font_size_small,
+                theme.text_color,
+            ));
+            for face in &exchange.defense.faces {
+                spawn_die(parent, *face, defense_sides, seconds, assets, config);
+            }
+        });
+
+    let summary = format!(
+        "{} against {}, {} damage, {} has {} health left",
+        breakdown(&exchange.attack),
+        breakdown(&exchange.defense),
+        exchange.damage,
+        target.name,
+        exchange.remaining
+    );
+    parent.spawn((
+        TextBundle {
+            visibility: Visibility::INVISIBLE,
+            ..text(summary.clone(), theme.label_font_size, theme.title_color)
+        },
+        Reveal {
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+            announcement: Some(summary),
+        },
+    ));
+}
+
+// "3 + 5 + 2 = 10" so the modifiers can be seen next to the dice
+fn breakdown(roll: &Roll) -> String {
+    let mut parts = roll
+        .faces
+        .iter()
+        .map(|face| face.to_string())
+        .collect::<Vec<_>>();
+    match roll.bonus {
+        0 => {}
+        bonus if bonus > 0 => parts.push(bonus.to_string()),
+        bonus => parts.push(format!("({})", bonus)),
+    }
+    if parts.len() > 1 {
+        format!("{} = {}", parts.join(" + "), roll.total())
+    } else {
+        roll.total().to_string()
+    }
+}
+
+fn spawn_die(
+    parent: &mut ChildBuilder,
+    face: u8,
+    sides: u8,
+    seconds: f32,
+    assets: &Assets,
+    config: &Config,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(DIE_SIZE), Val::Px(DIE_SIZE)),
+                margin: UiRect::all(Val::Px(6.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: config.palette.dice_face().into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    face.to_string(),
+                    TextStyle {
+                        font: assets.default_font.clone(),
+                        font_size: DIE_SIZE * 0.6,
+                        color: config.palette.dice_pips(),
+                    },
+                ),
+                RollingDie {
+                    face,
+                    sides,
+                    timer: Timer::from_seconds(seconds, TimerMode::Once),
+                },
+            ));
+        });
+}
+
+fn roll_dice(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_dice: Query<(Entity, &mut RollingDie, &mut Text)>,
+) {
+    let mut rng = rand::thread_rng();
+    for (entity, mut die, mut text) in q_dice.iter_mut() {
+        let face = if die.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<RollingDie>();
+            die.face
+        } else {
+            rng.gen_range(1..=die.sides.max(1))
+        };
+        text.sections[0].value = face.to_string();
+    }
+}
+
+fn reveal(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_reveal: Query<(Entity, &mut Reveal, &mut Visibility)>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    for (entity, mut reveal, mut visibility) in q_reveal.iter_mut() {
+        if reveal.timer.tick(time.delta()).finished() {
+            visibility.is_visible = true;
+            if let Some(announcement) = reveal.announcement.take() {
+                ev_announcement.send(Announcement::game(announcement));
+            }
+            commands.entity(entity).remove::<Reveal>();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn finish(
+    mut commands: Commands,
+    time: Res<Time>,
+    q_pending: Query<(), Or<(With<RollingDie>, With<Reveal>)>>,
+    watching: Option<ResMut<Watching>>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut game_state: ResMut<BevyState<State>>,
+) {
+    if !q_pending.is_empty() {
+        return;
+    }
+    let watched = watching.is_some_and(|mut watching| watching.timer.tick(time.delta()).finished());
+    if watched || confirm_pressed(&keyboard, &gamepads, &buttons) {
+        commands.remove_resource::<Watching>();
+        game_state.pop().expect("Failed to pop game state");
+    }
+}
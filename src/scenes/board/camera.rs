@@ -0,0 +1,260 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+
+use super::{game_log::GameLog, render, tokens::Token};
+use crate::{
+    board::TileId,
+    game::Config,
+    rules::{GameEvent, MatchState},
+};
+
+// world units a second the keys pan at when not zoomed
+const PAN_SPEED: f32 = 900.0;
+const ZOOM_STEP: f32 = 1.15;
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 2.0;
+// how quickly the camera catches up with where it should be, higher is snappier
+const CATCH_UP: f32 = 6.0;
+// touchpads scroll in pixels, wheels in lines
+const PIXELS_PER_LINE: f32 = 40.0;
+const PAN_KEYS: [(KeyCode, Vec2); 4] = [
+    (KeyCode::W, Vec2::Y),
+    (KeyCode::A, Vec2::NEG_X),
+    (KeyCode::S, Vec2::NEG_Y),
+    (KeyCode::D, Vec2::X),
+];
+
+// asks the camera to look at a tile, the minimap and other views send it
+pub struct FocusTile(pub TileId);
+
+// where the board camera is heading, the camera itself eases towards it every frame
+#[derive(Resource)]
+pub struct BoardCamera {
+    target: Vec2,
+    zoom: f32,
+    following: bool,
+    drag: Option<Vec2>,
+}
+
+impl Default for BoardCamera {
+    fn default() -> Self {
+        Self {
+            target: Vec2::ZERO,
+            zoom: 1.,
+            following: true,
+            drag: None,
+        }
+    }
+}
+
+impl BoardCamera {
+    fn zoom_by(&mut self, factor: f32) {
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    fn pan(&mut self, delta: Vec2) {
+        self.target += delta;
+        self.following = false;
+    }
+}
+
+// the world size the camera shows at its current zoom
+fn view_size(camera: &Camera, transform: &GlobalTransform) -> Option<Vec2> {
+    let size = camera.logical_viewport_size()?;
+    let min = camera.viewport_to_world(transform, Vec2::ZERO)?.origin;
+    let max = camera.viewport_to_world(transform, size)?.origin;
+    Some((max - min).truncate().abs())
+}
+
+// w a s d pan, the wheel or + and - zoom, dragging with the right or middle button pans and
+// f goes back to following the player
+#[allow(clippy::too_many_arguments)]
+pub fn camera_input(
+    time: Res<Time>,
+    keyboard: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    mut ev_wheel: EventReader<MouseWheel>,
+    windows: Res<Windows>,
+    log: Res<GameLog>,
+    q_camera: Query<(&Camera, &GlobalTransform)>,
+    mut board_camera: ResMut<BoardCamera>,
+) {
+    let wheel = ev_wheel
+        .iter()
+        .map(|wheel| match wheel.unit {
+            MouseScrollUnit::Line => wheel.y,
+            MouseScrollUnit::Pixel => wheel.y / PIXELS_PER_LINE,
+        })
+        .sum::<f32>();
+    // the open log scrolls with the wheel instead
+    if wheel != 0. && !log.is_open() {
+        board_camera.zoom_by(ZOOM_STEP.powf(-wheel));
+    }
+    if keyboard.any_just_pressed([KeyCode::Equals, KeyCode::NumpadAdd]) {
+        board_camera.zoom_by(1. / ZOOM_STEP);
+    }
+    if keyboard.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        board_camera.zoom_by(ZOOM_STEP);
+    }
+    if keyboard.just_pressed(KeyCode::F) {
+        board_camera.following = true;
+    }
+
+    let direction = PAN_KEYS
+        .iter()
+        .filter(|(key, _)| keyboard.pressed(*key))
+        .map(|(_, direction)| *direction)
+        .sum::<Vec2>();
+    if direction != Vec2::ZERO {
+        let speed = PAN_SPEED * board_camera.zoom * time.delta_seconds();
+        board_camera.pan(direction.normalize() * speed);
+    }
+
+    let dragging = mouse.any_pressed([MouseButton::Right, MouseButton::Middle]);
+    let cursor = windows
+        .get_primary()
+        .and_then(|window| window.cursor_position())
+        .filter(|_| dragging);
+    let Some(cursor) = cursor else {
+        board_camera.drag = None;
+        return;
+    };
+    if let (Some(last), Ok((camera, transform))) = (board_camera.drag, q_camera.get_single()) {
+        let world = |position| {
+            camera
+                .viewport_to_world(transform, position)
+                .map(|ray| ray.origin.truncate())
+        };
+        if let (Some(from), Some(to)) = (world(last), world(cursor)) {
+            if from != to {
+                board_camera.pan(from - to);
+            }
+        }
+    }
+    board_camera.drag = Some(cursor);
+}
+
+// the player's token is kept in sight, a new turn or a move starts following it again
+pub fn follow_player(
+    mut ev_game: EventReader<GameEvent>,
+    state: Res<MatchState>,
+    q_tokens: Query<(&Token, &Transform)>,
+    mut board_camera: ResMut<BoardCamera>,
+) {
+    if ev_game.iter().any(|event| {
+        matches!(
+            event,
+            GameEvent::TurnStarted { .. } | GameEvent::Moved { .. } | GameEvent::Teleported { .. }
+        )
+    }) {
+        board_camera.following = true;
+    }
+    if !board_camera.following {
+        return;
+    }
+    if let Some((_, transform)) = q_tokens
+        .iter()
+        .find(|(token, _)| token.seat == state.current)
+    {
+        board_camera.target = transform.translation.truncate();
+    }
+}
+
+pub fn focus_tile(
+    mut ev_focus: EventReader<FocusTile>,
+    state: Res<MatchState>,
+    mut board_camera: ResMut<BoardCamera>,
+) {
+    if let Some(FocusTile(tile)) = ev_focus.iter().last() {
+        board_camera.target = render::tile_position(&state.board, *tile);
+        board_camera.following = false;
+    }
+}
+
+// eases the camera towards its target, keeping the board in view: a board smaller than the
+// view stays centered so only a zoomed in or large board scrolls
+pub fn move_camera(
+    time: Res<Time>,
+    config: Res<Config>,
+    state: Res<MatchState>,
+    mut board_camera: ResMut<BoardCamera>,
+    mut q_camera: Query<(
+        &Camera,
+        &GlobalTransform,
+        &mut Transform,
+        &mut OrthographicProjection,
+    )>,
+) {
+    let Ok((camera, global_transform, mut transform, mut projection)) = q_camera.get_single_mut()
+    else {
+        return;
+    };
+    let Some(view) = view_size(camera, global_transform) else {
+        return;
+    };
+    // the view was measured at the scale of the last frame
+    let view = view / projection.scale * board_camera.zoom;
+
+    let bounds = render::board_bounds(&state.board);
+    let mut target = board_camera.target;
+    for axis in 0..2 {
+        let (min, max, half) = (bounds.min[axis], bounds.max[axis], view[axis] / 2.);
+        target[axis] = if max - min <= view[axis] {
+            (min + max) / 2.
+        } else {
+            target[axis].clamp(min + half, max - half)
+        };
+    }
+    if !board_camera.following {
+        // panning past the edge doesn't pile up
+        board_camera.target = target;
+    }
+
+    let ease = if config.reduced_motion {
+        1.
+    } else {
+        1. - (-CATCH_UP * time.delta_seconds()).exp()
+    };
+    let position = transform.translation.truncate();
+    let position = position.lerp(target, ease);
+    transform.translation.x = position.x;
+    transform.translation.y = position.y;
+    let scale = projection.scale + (board_camera.zoom - projection.scale) * ease;
+    if (scale - projection.scale).abs() > f32::EPSILON {
+        projection.scale = scale;
+    }
+}
+
+// the other scenes expect the camera the way the game set it up
+pub fn reset_camera(mut q_camera: Query<(&mut Transform, &mut OrthographicProjection)>) {
+    for (mut transform, mut projection) in &mut q_camera {
+        transform.translation.x = 0.;
+        transform.translation.y = 0.;
+        projection.scale = 1.;
+    }
+}
@@ -0,0 +1,183 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+use bevy_tweening::Animator;
+
+use super::OnBoardScene;
+use crate::{
+    effects::flip::{flip, Flippable},
+    game::{Assets, Config, SafeArea, Theme},
+    rules::{GameEvent, MatchState},
+};
+
+const CARD_WIDTH: f32 = 320.0;
+const CARD_HEIGHT: f32 = 420.0;
+const FLIP_MILLIS: u64 = 600;
+const CARD_SECONDS: f32 = 4.0;
+
+#[derive(Component)]
+pub struct CardRoot {
+    timer: Timer,
+}
+
+pub fn show_card(
+    mut commands: Commands,
+    mut ev_game: EventReader<GameEvent>,
+    state: Res<MatchState>,
+    assets: Res<Assets>,
+    theme: Res<Theme>,
+    config: Res<Config>,
+    q_root: Query<Entity, With<CardRoot>>,
+) {
+    for event in ev_game.iter() {
+        let GameEvent::CardDrawn { card, .. } = event else {
+            continue;
+        };
+        let Some(card) = state.data.cards.card(card) else {
+            continue;
+        };
+        for entity in &q_root {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        let text = |value: String, font_size: f32, color: Color| {
+            TextBundle::from_section(
+                value,
+                TextStyle {
+                    font: assets.default_font.clone(),
+                    font_size,
+                    color,
+                },
+            )
+            .with_style(Style {
+                margin: UiRect::all(Val::Px(8.0)),
+                max_size: Size::new(Val::Px(CARD_WIDTH - 40.0), Val::Undefined),
+                ..default()
+            })
+        };
+        let face = |visible: bool| NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            background_color: theme.panel_color.into(),
+            visibility: Visibility {
+                is_visible: visible,
+            },
+            ..default()
+        };
+
+        let mut back = None;
+        let mut front = None;
+        let mut card_node = None;
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                },
+                SafeArea,
+                CardRoot {
+                    timer: Timer::from_seconds(CARD_SECONDS, TimerMode::Once),
+                },
+                OnBoardScene,
+            ))
+            .with_children(|parent| {
+                let mut node = parent.spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(CARD_WIDTH), Val::Px(CARD_HEIGHT)),
+                        ..default()
+                    },
+                    ..default()
+                });
+                node.with_children(|parent| {
+                    // under reduced motion the card shows its front straight away
+                    back = Some(
+                        parent
+                            .spawn(face(!config.reduced_motion))
+                            .with_children(|parent| {
+                                parent.spawn(text(
+                                    "?".into(),
+                                    theme.title_font_size,
+                                    theme.title_color,
+                                ));
+                            })
+                            .id(),
+                    );
+                    front = Some(
+                        parent
+                            .spawn(face(config.reduced_motion))
+                            .with_children(|parent| {
+                                parent.spawn(text(
+                                    card.kind.name().into(),
+                                    theme.button_font_size_small,
+                                    theme.text_color,
+                                ));
+                                parent.spawn(text(
+                                    card.name.clone(),
+                                    theme.label_font_size,
+                                    theme.title_color,
+                                ));
+                                parent.spawn(text(
+                                    card.text.clone(),
+                                    theme.button_font_size_small,
+                                    theme.text_color,
+                                ));
+                            })
+                            .id(),
+                    );
+                });
+                card_node = Some(node.id());
+            });
+
+        if let (Some(card_node), Some(back), Some(front)) = (card_node, back, front) {
+            let mut node = commands.entity(card_node);
+            node.insert(Flippable { back, front });
+            if !config.reduced_motion {
+                node.insert(Animator::new(flip(FLIP_MILLIS)));
+            }
+        }
+    }
+}
+
+pub fn dismiss_card(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_root: Query<(Entity, &mut CardRoot)>,
+) {
+    for (entity, mut root) in &mut q_root {
+        if root.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
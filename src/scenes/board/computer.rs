@@ -0,0 +1,158 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::sync::{Arc, Mutex};
+
+use bevy::{prelude::*, tasks::AsyncComputeTaskPool};
+
+use super::tokens::TokenAnimations;
+use crate::{
+    ai::{candidates, Brain},
+    game::Controller,
+    rules::{Command, MatchState},
+};
+
+// a pause before each move so the computer can be followed
+const THINK_SECONDS: f32 = 0.8;
+
+// the brain goes to the task with a copy of the match and comes back with its answer
+type Thought = Arc<Mutex<Option<(Brain, Option<Command>)>>>;
+
+#[derive(Resource)]
+pub struct Computers {
+    controllers: Vec<Controller>,
+    brains: Vec<Option<Brain>>,
+    seats: Vec<bool>,
+    thinking: Option<Thought>,
+    timer: Timer,
+}
+
+impl Computers {
+    pub fn new(seats: &[Controller], seed: u64) -> Self {
+        let brains = seats
+            .iter()
+            .enumerate()
+            .map(|(seat, controller)| match controller {
+                Controller::Human => None,
+                Controller::Computer(difficulty) => Some(Brain::new(seat, *difficulty, seed)),
+            })
+            .collect::<Vec<_>>();
+        Self {
+            controllers: seats.to_vec(),
+            seats: brains.iter().map(Option::is_some).collect(),
+            brains,
+            thinking: None,
+            timer: Timer::from_seconds(THINK_SECONDS, TimerMode::Repeating),
+        }
+    }
+
+    // a replay plays every seat, the commands come from the recording instead of a brain
+    pub fn watching(players: usize) -> Self {
+        Self {
+            controllers: Vec::new(),
+            brains: (0..players).map(|_| None).collect(),
+            seats: vec![true; players],
+            thinking: None,
+            timer: Timer::from_seconds(THINK_SECONDS, TimerMode::Repeating),
+        }
+    }
+
+    // who was picked to play each seat, nobody for a replay
+    pub fn controllers(&self) -> &[Controller] {
+        &self.controllers
+    }
+
+    pub fn plays(&self, seat: usize) -> bool {
+        self.seats.get(seat).copied().unwrap_or_default()
+    }
+
+    // the keyboard and the gamepad only drive the seats no computer plays
+    pub fn human_turn(&self, state: &MatchState) -> bool {
+        !self.plays(state.current)
+    }
+}
+
+// the brain of the seat decides on the task pool, so a long search never holds a frame,
+// a fight it picks still plays out on the battle scene
+pub fn think(
+    time: Res<Time>,
+    state: Res<MatchState>,
+    animations: Res<TokenAnimations>,
+    mut computers: ResMut<Computers>,
+    mut ev_command: EventWriter<Command>,
+) {
+    if let Some(thought) = &computers.thinking {
+        let answer = thought
+            .lock()
+            .expect("computer thought lock poisoned")
+            .take();
+        let Some((brain, command)) = answer else {
+            return;
+        };
+        let seat = brain.seat();
+        computers.brains[seat] = Some(brain);
+        computers.thinking = None;
+        // an answer the rules won't take would leave the match waiting on this seat forever
+        let command = match command {
+            Some(command) if state.clone().apply(command.clone()).is_ok() => Some(command),
+            None if state.winner().is_some() => None,
+            command => {
+                let fallback = candidates(&state, seat).into_iter().next();
+                error!(
+                    "computer in seat {} answered {:?}, playing {:?} instead",
+                    seat, command, fallback
+                );
+                fallback
+            }
+        };
+        if let Some(command) = command {
+            ev_command.send(command);
+        }
+        return;
+    }
+
+    if computers.human_turn(&state) || !animations.is_idle() {
+        computers.timer.reset();
+        return;
+    }
+    if !computers.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Some(mut brain) = computers
+        .brains
+        .get_mut(state.current)
+        .and_then(Option::take)
+    else {
+        return;
+    };
+    let thought = Thought::default();
+    let task_thought = thought.clone();
+    let state = state.clone();
+    AsyncComputeTaskPool::get()
+        .spawn(async move {
+            let command = brain.decide(&state);
+            *task_thought.lock().expect("computer thought lock poisoned") = Some((brain, command));
+        })
+        .detach();
+    computers.thinking = Some(thought);
+}
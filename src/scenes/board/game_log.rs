@@ -0,0 +1,414 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::{fs, io, path::Path};
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+
+use super::{inventory::InventoryPanel, OnBoardScene};
+use crate::{
+    game::{Assets, SafeArea, Theme},
+    rules::{Fighter, GameEvent, MatchState},
+};
+
+const PANEL_WIDTH: f32 = 640.0;
+const VISIBLE_LINES: usize = 14;
+// touchpads scroll in pixels, wheels in lines
+const PIXELS_PER_LINE: f32 = 40.0;
+
+#[derive(Component)]
+pub struct LogRoot;
+
+struct LogEntry {
+    turn: u32,
+    text: String,
+}
+
+// everything that happened in the match, in order, as it reads in the log
+#[derive(Resource, Default)]
+pub struct GameLog {
+    entries: Vec<LogEntry>,
+    open: bool,
+    // lines scrolled up from the newest one
+    scroll: usize,
+}
+
+impl GameLog {
+    // a plain text copy of the log to attach to bug reports
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder)?;
+        }
+        let text = self
+            .entries
+            .iter()
+            .map(|entry| format!("[turn {}] {}\n", entry.turn, entry.text))
+            .collect::<String>();
+        fs::write(path, text)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn note(&mut self, turn: u32, text: impl Into<String>) {
+        self.entries.push(LogEntry {
+            turn,
+            text: text.into(),
+        });
+    }
+
+    fn scroll_by(&mut self, lines: isize) {
+        let most = self.entries.len().saturating_sub(VISIBLE_LINES);
+        let scroll = if lines < 0 {
+            self.scroll.saturating_sub(lines.unsigned_abs())
+        } else {
+            self.scroll + lines as usize
+        };
+        self.scroll = scroll.min(most);
+    }
+}
+
+// how an event reads, for the log and for the announcer
+pub fn describe(event: &GameEvent, state: &MatchState) -> String {
+    let name = |seat: &usize| state.players[*seat].name.as_str();
+    let fighter = |fighter: &Fighter| state.combatant(*fighter).name;
+    match event {
+        GameEvent::TurnStarted { seat, turn } => {
+            format!("Turn {}, {} to roll", turn, name(seat))
+        }
+        GameEvent::Rolled { seat, roll } => format!("{} rolled {}", name(seat), roll),
+        GameEvent::SpellCast {
+            seat,
+            spell,
+            target,
+        } => {
+            let spell = state
+                .data
+                .magic
+                .spell(spell)
+                .map_or(spell.as_str(), |spell| spell.name.as_str());
+            match target {
+                Some(target) => format!("{} cast {} on {}", name(seat), spell, name(target)),
+                None => format!("{} cast {}", name(seat), spell),
+            }
+        }
+        GameEvent::RollChanged { seat, roll } => {
+            format!("{} now moves {}", name(seat), roll)
+        }
+        GameEvent::Teleported { seat, tile } => format!(
+            "{} teleported to {}",
+            name(seat),
+            state.board.tile(*tile).kind.name()
+        ),
+        GameEvent::Hindered { seat, steps } => {
+            format!("{} will move {} less next roll", name(seat), steps)
+        }
+        GameEvent::Moved { seat, path } => {
+            let tile = path
+                .last()
+                .map_or("the same tile", |tile| state.board.tile(*tile).kind.name());
+            let tiles = if path.len() == 1 { "tile" } else { "tiles" };
+            format!("{} moved {} {} to {}", name(seat), path.len(), tiles, tile)
+        }
+        GameEvent::ForkReached { seat, options, .. } => {
+            format!("{} reached a fork with {} paths", name(seat), options.len())
+        }
+        GameEvent::Landed { seat, kind, .. } => {
+            format!("{} landed on {}", name(seat), kind.name())
+        }
+        GameEvent::BattleStarted { attacker, defender } => {
+            format!("{} attacks {}", fighter(attacker), fighter(defender))
+        }
+        GameEvent::BattleResolved(report) => {
+            let hits = report
+                .exchanges
+                .iter()
+                .map(|exchange| {
+                    format!(
+                        "{} struck {} against {} for {} damage",
+                        report.combatant(exchange.striker).name,
+                        exchange.attack.total(),
+                        exchange.defense.total(),
+                        exchange.damage
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!(
+                "{} fought {}: {}",
+                report.attacker.name,
+                report.defender.name,
+                hits.join("; ")
+            )
+        }
+        GameEvent::Fled { seat, from, damage } => format!(
+            "{} ran away from {} losing {} health",
+            name(seat),
+            fighter(from),
+            damage
+        ),
+        GameEvent::Defeated { name, .. } => format!("{} was defeated", name),
+        GameEvent::Looted { seat, gold, items } => {
+            let mut found = Vec::new();
+            if *gold > 0 {
+                found.push(format!("{} gold", gold));
+            }
+            found.extend(
+                items
+                    .iter()
+                    .map(|item| state.data.items.name(item).to_string()),
+            );
+            format!("{} found {}", name(seat), found.join(", "))
+        }
+        GameEvent::InventoryFull { seat, item } => format!(
+            "{} had no room for {} and left it behind",
+            name(seat),
+            state.data.items.name(item)
+        ),
+        GameEvent::ShopOpened { seat, tile } => {
+            let shop = state
+                .shop_at(*tile)
+                .map_or("a shop", |(_, def)| def.name.as_str());
+            format!("{} entered {}", name(seat), shop)
+        }
+        GameEvent::Bought { seat, goods, price } => format!(
+            "{} bought {} for {} gold",
+            name(seat),
+            state.data.goods_name(goods),
+            price
+        ),
+        GameEvent::Sold { seat, goods, price } => format!(
+            "{} sold {} for {} gold",
+            name(seat),
+            state.data.goods_name(goods),
+            price
+        ),
+        GameEvent::ShopClosed { seat } => format!("{} left the shop", name(seat)),
+        GameEvent::CardDrawn { seat, card } => match state.data.cards.card(card) {
+            Some(card) => format!("{} drew {}, {}", name(seat), card.name, card.text),
+            None => format!("{} drew {}", name(seat), card),
+        },
+        GameEvent::Damaged { seat, damage } => {
+            format!("{} took {} damage", name(seat), damage)
+        }
+        GameEvent::GoldChanged { seat, amount } if *amount < 0 => {
+            format!("{} lost {} gold", name(seat), -amount)
+        }
+        GameEvent::GoldChanged { seat, amount } => {
+            format!("{} gained {} gold", name(seat), amount)
+        }
+        GameEvent::WeatherChanged {
+            weather: Some(weather),
+        } => format!("{} for {} rounds", weather.name, weather.rounds),
+        GameEvent::WeatherChanged { weather: None } => "The weather cleared".into(),
+        GameEvent::ShopsRestocked => "Every shop restocked".into(),
+        GameEvent::Eliminated { seat } => format!("{} is out of the match", name(seat)),
+        GameEvent::MatchEnded { winner } => format!("{} wins the match", name(winner)),
+        GameEvent::MonsterSpawned { kind, tile, .. } => {
+            let monster = state
+                .data
+                .monsters
+                .monster(kind)
+                .map_or(kind.as_str(), |def| def.name.as_str());
+            format!(
+                "A {} appeared on {}",
+                monster,
+                state.board.tile(*tile).kind.name()
+            )
+        }
+        GameEvent::MonsterMoved { monster, tile } => {
+            let monster = state
+                .monsters
+                .iter()
+                .find(|other| other.id == *monster)
+                .and_then(|other| state.data.monsters.monster(&other.kind))
+                .map_or("A monster", |def| def.name.as_str());
+            format!(
+                "{} moved to {}",
+                monster,
+                state.board.tile(*tile).kind.name()
+            )
+        }
+        GameEvent::Equipped { seat, item, .. } => {
+            format!("{} equipped {}", name(seat), state.data.items.name(item))
+        }
+        GameEvent::Unequipped { seat, item, .. } => {
+            format!("{} put away {}", name(seat), state.data.items.name(item))
+        }
+        GameEvent::ItemUsed { seat, item } => {
+            format!("{} used {}", name(seat), state.data.items.name(item))
+        }
+        GameEvent::Healed { seat, health } => {
+            format!("{} recovered {} health", name(seat), health)
+        }
+        GameEvent::RuneCollected { seat, rune } => {
+            let rune = state
+                .data
+                .magic
+                .rune(rune)
+                .map_or(rune.as_str(), |rune| rune.name.as_str());
+            format!("{} collected a {}", name(seat), rune)
+        }
+        GameEvent::SpellLearned { seat, spell } => {
+            let spell = state
+                .data
+                .magic
+                .spell(spell)
+                .map_or(spell.as_str(), |spell| spell.name.as_str());
+            format!("{} learned {}", name(seat), spell)
+        }
+    }
+}
+
+pub fn record_events(
+    mut ev_game: EventReader<GameEvent>,
+    state: Res<MatchState>,
+    mut log: ResMut<GameLog>,
+) {
+    for event in ev_game.iter() {
+        log.note(state.turn, describe(event, &state));
+    }
+}
+
+// l or select opens the log, page up and down or the wheel scroll it
+pub fn log_input(
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut ev_wheel: EventReader<MouseWheel>,
+    mut log: ResMut<GameLog>,
+) {
+    let gamepad_pressed = |button: GamepadButtonType| {
+        gamepads
+            .iter()
+            .any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button)))
+    };
+    if keyboard.just_pressed(KeyCode::L) || gamepad_pressed(GamepadButtonType::Select) {
+        log.open = !log.open;
+        log.scroll = 0;
+    }
+    let wheel = ev_wheel
+        .iter()
+        .map(|wheel| match wheel.unit {
+            MouseScrollUnit::Line => wheel.y,
+            MouseScrollUnit::Pixel => wheel.y / PIXELS_PER_LINE,
+        })
+        .sum::<f32>();
+    if !log.open {
+        return;
+    }
+    let mut lines = wheel.round() as isize;
+    if keyboard.just_pressed(KeyCode::PageUp) || gamepad_pressed(GamepadButtonType::LeftTrigger) {
+        lines += VISIBLE_LINES as isize;
+    }
+    if keyboard.just_pressed(KeyCode::PageDown) || gamepad_pressed(GamepadButtonType::RightTrigger)
+    {
+        lines -= VISIBLE_LINES as isize;
+    }
+    if lines != 0 {
+        log.scroll_by(lines);
+    }
+}
+
+// the newest lines at the bottom, the log makes way for the inventory on the same side
+pub fn show_log(
+    mut commands: Commands,
+    log: Res<GameLog>,
+    panel: Res<InventoryPanel>,
+    assets: Res<Assets>,
+    theme: Res<Theme>,
+    q_root: Query<Entity, With<LogRoot>>,
+) {
+    if !log.is_changed() && !panel.is_changed() && !theme.is_changed() {
+        return;
+    }
+    for entity in &q_root {
+        commands.entity(entity).despawn_recursive();
+    }
+    if !log.open || panel.open {
+        return;
+    }
+
+    let end = log.entries.len() - log.scroll.min(log.entries.len());
+    let start = end.saturating_sub(VISIBLE_LINES);
+    let text = |value: String, color: Color| {
+        TextBundle::from_section(
+            value,
+            TextStyle {
+                font: assets.default_font.clone(),
+                font_size: theme.button_font_size_small,
+                color,
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(2.0)),
+            max_size: Size::new(Val::Px(PANEL_WIDTH - 40.0), Val::Undefined),
+            ..default()
+        })
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    justify_content: JustifyContent::FlexEnd,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            SafeArea,
+            LogRoot,
+            OnBoardScene,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        size: Size::new(Val::Px(PANEL_WIDTH), Val::Auto),
+                        padding: UiRect::all(Val::Px(20.0)),
+                        margin: UiRect::right(Val::Px(20.0)),
+                        ..default()
+                    },
+                    background_color: theme.panel_color.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(text(
+                        format!("Game log, {} of {}", end, log.entries.len()),
+                        theme.title_color,
+                    ));
+                    for entry in &log.entries[start..end] {
+                        parent.spawn(text(
+                            format!("{}: {}", entry.turn, entry.text),
+                            theme.text_color,
+                        ));
+                    }
+                });
+        });
+}
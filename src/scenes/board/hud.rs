@@ -0,0 +1,224 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+
+use super::OnBoardScene;
+use crate::{
+    dice::Roll,
+    game::{Assets, Config, SafeArea, Theme},
+    rules::{GameEvent, MatchState, Phase},
+};
+
+const PANEL_WIDTH: f32 = 360.0;
+const PORTRAIT_SIZE: f32 = 56.0;
+const PORTRAIT_TEXT_COLOR: Color = Color::BLACK;
+
+#[derive(Component)]
+pub struct HudRoot;
+
+// the roll stays on the hud after the move so it can still be read
+#[derive(Resource, Default)]
+pub struct LastRoll(Option<(usize, Roll)>);
+
+pub fn track_roll(mut ev_game: EventReader<GameEvent>, mut last_roll: ResMut<LastRoll>) {
+    for event in ev_game.iter() {
+        if let GameEvent::Rolled { seat, roll } | GameEvent::RollChanged { seat, roll } = event {
+            last_roll.0 = Some((*seat, roll.clone()));
+        }
+    }
+}
+
+// "3 + 5 + 1 = 9", every die and the bonus on its own
+fn breakdown(roll: &Roll) -> String {
+    let mut text = roll
+        .faces
+        .iter()
+        .map(|face| face.to_string())
+        .collect::<Vec<_>>()
+        .join(" + ");
+    match roll.bonus {
+        0 => {}
+        bonus if bonus > 0 => text = format!("{} + {}", text, bonus),
+        bonus => text = format!("{} - {}", text, -bonus),
+    }
+    if roll.faces.len() > 1 || roll.bonus != 0 {
+        text = format!("{} = {}", text, roll.total());
+    }
+    text
+}
+
+fn describe_phase(state: &MatchState) -> String {
+    match &state.phase {
+        Phase::Roll => "Rolling".into(),
+        Phase::Rolled { roll } => format!("Moving {}", roll.total()),
+        Phase::Choose { remaining, .. } => format!("Choosing a path, {} to go", remaining),
+        Phase::Battle { .. } => "Fighting".into(),
+        Phase::Shop { .. } => "Shopping".into(),
+        Phase::Over { .. } => "Match over".into(),
+    }
+}
+
+// a panel for every player down the left side, the one playing highlighted, with the turn
+// below them, rebuilt whenever the match changes
+pub fn show_hud(
+    mut commands: Commands,
+    state: Res<MatchState>,
+    last_roll: Res<LastRoll>,
+    config: Res<Config>,
+    assets: Res<Assets>,
+    theme: Res<Theme>,
+    q_root: Query<Entity, With<HudRoot>>,
+) {
+    if !state.is_changed()
+        && !last_roll.is_changed()
+        && !config.is_changed()
+        && !theme.is_changed()
+        && !q_root.is_empty()
+    {
+        return;
+    }
+    for entity in &q_root {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let text = |value: String, color: Color| {
+        TextBundle::from_section(
+            value,
+            TextStyle {
+                font: assets.default_font.clone(),
+                font_size: theme.button_font_size_small,
+                color,
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(2.0)),
+            ..default()
+        })
+    };
+    let panel = |color: Color| NodeBundle {
+        style: Style {
+            size: Size::new(Val::Px(PANEL_WIDTH), Val::Auto),
+            padding: UiRect::all(Val::Px(10.0)),
+            margin: UiRect::new(Val::Px(20.0), Val::Px(0.0), Val::Px(6.0), Val::Px(6.0)),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: color.into(),
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::FlexStart,
+                    ..default()
+                },
+                ..default()
+            },
+            SafeArea,
+            HudRoot,
+            OnBoardScene,
+        ))
+        .with_children(|parent| {
+            for player in &state.players {
+                let playing = player.seat == state.current;
+                let background = if playing {
+                    theme.hovered_selected_color
+                } else {
+                    theme.normal_color
+                };
+                parent.spawn(panel(background)).with_children(|parent| {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(PORTRAIT_SIZE), Val::Px(PORTRAIT_SIZE)),
+                                margin: UiRect::right(Val::Px(10.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: config.palette.player_color(player.seat).into(),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            let initial = player.name.chars().next().unwrap_or('?');
+                            parent.spawn(text(initial.to_string(), PORTRAIT_TEXT_COLOR));
+                        });
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn(text(player.name.clone(), theme.title_color));
+                            let health = if player.out {
+                                "Out of the match".to_string()
+                            } else {
+                                format!("HP {}/{}", player.health, player.stats.max_health)
+                            };
+                            parent.spawn(text(health, theme.text_color));
+                            parent.spawn(text(
+                                format!("Gold {}  Runes {}", player.gold, player.rune_count()),
+                                theme.text_color,
+                            ));
+                        });
+                });
+            }
+
+            parent
+                .spawn(panel(theme.normal_color))
+                .with_children(|parent| {
+                    let mut lines = vec![
+                        format!("Turn {}, {} plays", state.turn, state.current_player().name),
+                        describe_phase(&state),
+                    ];
+                    if let Some((seat, roll)) = &last_roll.0 {
+                        lines.push(format!(
+                            "{} rolled {}",
+                            state.players[*seat].name,
+                            breakdown(roll)
+                        ));
+                    }
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for line in lines {
+                                parent.spawn(text(line, theme.text_color));
+                            }
+                        });
+                });
+        });
+}
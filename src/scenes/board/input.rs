@@ -0,0 +1,367 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+
+use super::{
+    super::confirm_pressed, camera::FocusTile, computer::Computers, inventory::InventoryPanel,
+    render, tokens::TokenAnimations, OnBoardScene,
+};
+use crate::{
+    board::TileId,
+    game::{Announcement, Assets, SafeArea, Theme},
+    rules::{Command, History, MatchState, Phase},
+};
+
+const MARKER_Z: f32 = 0.5;
+const MARKER_SIZE: f32 = render::TILE_SIZE + 30.;
+const MARKER_COLOR: Color = Color::rgba(1., 1., 1., 0.35);
+const MARKER_SELECTED_COLOR: Color = Color::rgba(1., 0.9, 0.2, 0.9);
+
+#[derive(Resource, Default)]
+pub struct ForkSelection {
+    index: usize,
+}
+
+#[derive(Component)]
+pub struct ForkMarker {
+    index: usize,
+    tile: TileId,
+}
+
+#[derive(Component)]
+pub struct TurnHint;
+
+#[allow(clippy::too_many_arguments)]
+pub fn roll(
+    state: Res<MatchState>,
+    animations: Res<TokenAnimations>,
+    computers: Res<Computers>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    panel: Res<InventoryPanel>,
+    mut ev_command: EventWriter<Command>,
+) {
+    if panel.open
+        || !animations.is_idle()
+        || !computers.human_turn(&state)
+        || !confirm_pressed(&keyboard, &gamepads, &buttons)
+    {
+        return;
+    }
+    match state.phase {
+        Phase::Roll => ev_command.send(Command::Roll),
+        Phase::Rolled { .. } => ev_command.send(Command::Move),
+        Phase::Battle { .. } => ev_command.send(Command::Fight),
+        Phase::Choose { .. } | Phase::Shop { .. } | Phase::Over { .. } => {}
+    }
+}
+
+// running away from a battle instead of fighting it, for some health
+pub fn flee(
+    state: Res<MatchState>,
+    animations: Res<TokenAnimations>,
+    computers: Res<Computers>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut ev_command: EventWriter<Command>,
+) {
+    if !matches!(state.phase, Phase::Battle { .. })
+        || !animations.is_idle()
+        || !computers.human_turn(&state)
+    {
+        return;
+    }
+    let gamepad_pressed = gamepads
+        .iter()
+        .any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::East)));
+    if keyboard.just_pressed(KeyCode::R) || gamepad_pressed {
+        ev_command.send(Command::Flee);
+    }
+}
+
+const SPELL_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+// number keys cast the spells listed in the hint, spells on opponents hit the next player
+// still in the match, with nobody left to hit they are not cast
+pub fn cast(
+    state: Res<MatchState>,
+    animations: Res<TokenAnimations>,
+    computers: Res<Computers>,
+    keyboard: Res<Input<KeyCode>>,
+    panel: Res<InventoryPanel>,
+    mut ev_command: EventWriter<Command>,
+) {
+    if panel.open || !animations.is_idle() || !computers.human_turn(&state) {
+        return;
+    }
+    let spells = state.castable_spells();
+    for (key, spell) in SPELL_KEYS.iter().zip(spells) {
+        if keyboard.just_pressed(*key) {
+            let target = if spell.effect.needs_target() {
+                let seats = state.players.len();
+                let next = (1..seats)
+                    .map(|offset| (state.current + offset) % seats)
+                    .find(|seat| !state.players[*seat].out);
+                let Some(next) = next else {
+                    continue;
+                };
+                Some(next)
+            } else {
+                None
+            };
+            ev_command.send(Command::Cast {
+                spell: spell.id.clone(),
+                target,
+            });
+        }
+    }
+}
+
+pub fn show_fork(
+    mut commands: Commands,
+    state: Res<MatchState>,
+    animations: Res<TokenAnimations>,
+    mut selection: ResMut<ForkSelection>,
+    q_markers: Query<Entity, With<ForkMarker>>,
+) {
+    let options = match &state.phase {
+        Phase::Choose { options, .. } if animations.is_idle() => options.as_slice(),
+        _ => {
+            for entity in &q_markers {
+                commands.entity(entity).despawn_recursive();
+            }
+            return;
+        }
+    };
+    if !q_markers.is_empty() {
+        return;
+    }
+
+    selection.index = 0;
+    for (index, tile) in options.iter().enumerate() {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: MARKER_COLOR,
+                    custom_size: Some(Vec2::splat(MARKER_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_translation(
+                    render::tile_position(&state.board, *tile).extend(MARKER_Z),
+                ),
+                ..default()
+            },
+            ForkMarker { index, tile: *tile },
+            OnBoardScene,
+        ));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn choose_fork(
+    state: Res<MatchState>,
+    computers: Res<Computers>,
+    mut selection: ResMut<ForkSelection>,
+    mut q_markers: Query<(&ForkMarker, &mut Sprite)>,
+    q_new_markers: Query<(), Added<ForkMarker>>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mouse: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    q_camera: Query<(&Camera, &GlobalTransform)>,
+    mut ev_command: EventWriter<Command>,
+    mut ev_focus: EventWriter<FocusTile>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    let count = q_markers.iter().count();
+    if count == 0 || !computers.human_turn(&state) {
+        return;
+    }
+
+    let gamepad_pressed = |button_type| {
+        gamepads
+            .iter()
+            .any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+    };
+    let previous = keyboard.any_just_pressed([KeyCode::Left, KeyCode::Up])
+        || gamepad_pressed(GamepadButtonType::DPadLeft)
+        || gamepad_pressed(GamepadButtonType::DPadUp);
+    let next = keyboard.any_just_pressed([KeyCode::Right, KeyCode::Down, KeyCode::Tab])
+        || gamepad_pressed(GamepadButtonType::DPadRight)
+        || gamepad_pressed(GamepadButtonType::DPadDown);
+    let old_index = selection.index;
+    if previous {
+        selection.index = (selection.index + count - 1) % count;
+    }
+    if next {
+        selection.index = (selection.index + 1) % count;
+    }
+
+    let mut chosen = None;
+    if mouse.just_pressed(MouseButton::Left) {
+        let cursor = windows
+            .get_primary()
+            .and_then(|window| window.cursor_position());
+        if let (Some(cursor), Ok((camera, camera_transform))) = (cursor, q_camera.get_single()) {
+            if let Some(ray) = camera.viewport_to_world(camera_transform, cursor) {
+                let world = ray.origin.truncate();
+                chosen = q_markers
+                    .iter()
+                    .find(|(marker, _)| {
+                        let center = render::tile_position(&state.board, marker.tile);
+                        Rect::from_center_size(center, Vec2::splat(MARKER_SIZE)).contains(world)
+                    })
+                    .map(|(marker, _)| marker.tile);
+            }
+        }
+    }
+    if chosen.is_none() && confirm_pressed(&keyboard, &gamepads, &buttons) {
+        chosen = q_markers
+            .iter()
+            .find(|(marker, _)| marker.index == selection.index)
+            .map(|(marker, _)| marker.tile);
+    }
+
+    for (marker, mut sprite) in q_markers.iter_mut() {
+        let selected = marker.index == selection.index;
+        sprite.color = if selected {
+            MARKER_SELECTED_COLOR
+        } else {
+            MARKER_COLOR
+        };
+        if selected && old_index != selection.index {
+            ev_focus.send(FocusTile(marker.tile));
+        }
+        if selected && (old_index != selection.index || !q_new_markers.is_empty()) {
+            let kind = state.board.tile(marker.tile).kind;
+            ev_announcement.send(Announcement::focus(format!("Path to {}", kind.name())));
+        }
+    }
+
+    if let Some(tile) = chosen {
+        ev_command.send(Command::Choose(tile));
+    }
+}
+
+pub fn spawn_hint(commands: &mut Commands, assets: &Assets, theme: &Theme) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::FlexStart,
+                    ..default()
+                },
+                ..default()
+            },
+            SafeArea,
+            OnBoardScene,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.default_font.clone(),
+                        font_size: theme.label_font_size,
+                        color: theme.title_color,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                }),
+                TurnHint,
+            ));
+        });
+}
+
+pub fn update_hint(
+    state: Res<MatchState>,
+    animations: Res<TokenAnimations>,
+    computers: Res<Computers>,
+    history: Res<History>,
+    mut q_hint: Query<&mut Text, With<TurnHint>>,
+) {
+    if !state.is_changed() && !animations.is_changed() {
+        return;
+    }
+    let player = state.current_player();
+    let hint = if !animations.is_idle() {
+        format!("{} is moving", player.name)
+    } else if !computers.human_turn(&state) && state.winner().is_none() {
+        format!("{} is thinking", player.name)
+    } else {
+        let action = match &state.phase {
+            Phase::Roll => format!(
+                "{}, press Space to roll, I for the inventory, L for the log",
+                player.name
+            ),
+            Phase::Rolled { roll } => {
+                format!("{} rolled {}, press Space to move", player.name, roll)
+            }
+            Phase::Choose { .. } => format!("{}, choose a path", player.name),
+            Phase::Shop { .. } => format!("{} is shopping", player.name),
+            Phase::Battle { attacker, defender } => format!(
+                "{} attacks {}, press Space to fight or R to run away for {} health",
+                state.combatant(*attacker).name,
+                state.combatant(*defender).name,
+                state.data.battle.flee_damage
+            ),
+            Phase::Over { winner } => format!("{} wins!", state.players[*winner].name),
+        };
+        let action = if history.can_undo() {
+            format!("{}, Z to undo", action)
+        } else {
+            action
+        };
+        let spells = state
+            .castable_spells()
+            .iter()
+            .enumerate()
+            .map(|(index, spell)| format!("{}: {}", index + 1, spell.name))
+            .collect::<Vec<_>>();
+        if spells.is_empty() {
+            action
+        } else {
+            format!("{}\n{}", action, spells.join("  "))
+        }
+    };
+    for mut text in q_hint.iter_mut() {
+        text.sections[0].value = hint.clone();
+    }
+}
@@ -0,0 +1,280 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+
+use super::{super::confirm_pressed, computer::Computers, tokens::TokenAnimations, OnBoardScene};
+use crate::{
+    game::{Announcement, Assets, SafeArea, Theme},
+    rules::{Command, Fighter, MatchState, Phase, Player, Slot},
+};
+
+const PANEL_WIDTH: f32 = 560.0;
+const SLOTS: [Slot; 3] = [Slot::Weapon, Slot::Armor, Slot::Trinket];
+
+#[derive(Resource, Default)]
+pub struct InventoryPanel {
+    pub open: bool,
+    index: usize,
+}
+
+#[derive(Component)]
+pub struct InventoryRoot;
+
+enum Entry {
+    Equipped(Slot),
+    Item(usize),
+}
+
+// what can be picked in the panel, equipped items first and then the backpack
+fn entries(state: &MatchState, player: &Player) -> Vec<(Entry, String)> {
+    let items = &state.data.items;
+    let equipped = player.equipment.iter().map(|(slot, item)| {
+        (
+            Entry::Equipped(*slot),
+            format!("{}: {}", slot.name(), items.name(item)),
+        )
+    });
+    let backpack = player.inventory.iter().enumerate().map(|(index, item)| {
+        let text = match items.item(item) {
+            Some(def) if !def.description.is_empty() => {
+                format!("{}, {}", def.name, def.description)
+            }
+            _ => items.name(item).to_string(),
+        };
+        (Entry::Item(index), text)
+    });
+    equipped.chain(backpack).collect()
+}
+
+fn can_open(state: &MatchState, animations: &TokenAnimations, computers: &Computers) -> bool {
+    matches!(state.phase, Phase::Roll | Phase::Rolled { .. })
+        && animations.is_idle()
+        && computers.human_turn(state)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn toggle_inventory(
+    state: Res<MatchState>,
+    animations: Res<TokenAnimations>,
+    computers: Res<Computers>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut panel: ResMut<InventoryPanel>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    if !can_open(&state, &animations, &computers) {
+        if panel.open {
+            panel.open = false;
+        }
+        return;
+    }
+    let pressed = keyboard.just_pressed(KeyCode::I)
+        || gamepads.iter().any(|gamepad| {
+            buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::North))
+        });
+    if pressed {
+        panel.open = !panel.open;
+        panel.index = 0;
+        let player = state.current_player();
+        ev_announcement.send(Announcement::scene(if panel.open {
+            format!(
+                "{} inventory, {} health, {} gold",
+                player.name, player.health, player.gold
+            )
+        } else {
+            "Inventory closed".to_string()
+        }));
+    }
+}
+
+pub fn navigate_inventory(
+    state: Res<MatchState>,
+    mut panel: ResMut<InventoryPanel>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut ev_command: EventWriter<Command>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    if !panel.open {
+        return;
+    }
+    let entries = entries(&state, state.current_player());
+    if entries.is_empty() {
+        return;
+    }
+
+    let gamepad_pressed = |button_type| {
+        gamepads
+            .iter()
+            .any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+    };
+    let count = entries.len();
+    let old_index = panel.index;
+    if keyboard.just_pressed(KeyCode::Up) || gamepad_pressed(GamepadButtonType::DPadUp) {
+        panel.index = (panel.index + count - 1) % count;
+    }
+    if keyboard.any_just_pressed([KeyCode::Down, KeyCode::Tab])
+        || gamepad_pressed(GamepadButtonType::DPadDown)
+    {
+        panel.index = (panel.index + 1) % count;
+    }
+    panel.index = panel.index.min(count - 1);
+    if panel.index != old_index {
+        ev_announcement.send(Announcement::focus(entries[panel.index].1.clone()));
+    }
+
+    if confirm_pressed(&keyboard, &gamepads, &buttons) {
+        let command = match entries[panel.index].0 {
+            Entry::Equipped(slot) => Command::Unequip(slot),
+            Entry::Item(index) => {
+                let usable = state
+                    .data
+                    .items
+                    .item(&state.current_player().inventory[index])
+                    .is_some_and(|item| item.slot.is_none());
+                if usable {
+                    Command::UseItem(index)
+                } else {
+                    Command::Equip(index)
+                }
+            }
+        };
+        ev_command.send(command);
+    }
+}
+
+pub fn show_inventory(
+    mut commands: Commands,
+    state: Res<MatchState>,
+    panel: Res<InventoryPanel>,
+    assets: Res<Assets>,
+    theme: Res<Theme>,
+    q_root: Query<Entity, With<InventoryRoot>>,
+) {
+    if !state.is_changed() && !panel.is_changed() && !theme.is_changed() {
+        return;
+    }
+    for entity in &q_root {
+        commands.entity(entity).despawn_recursive();
+    }
+    if !panel.open {
+        return;
+    }
+
+    let player = state.current_player();
+    let combatant = state.combatant(Fighter::Player(player.seat));
+    let entries = entries(&state, player);
+    let text = |value: String, font_size: f32, color: Color| {
+        TextBundle::from_section(
+            value,
+            TextStyle {
+                font: assets.default_font.clone(),
+                font_size,
+                color,
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(4.0)),
+            ..default()
+        })
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    justify_content: JustifyContent::FlexEnd,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            SafeArea,
+            InventoryRoot,
+            OnBoardScene,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        size: Size::new(Val::Px(PANEL_WIDTH), Val::Auto),
+                        padding: UiRect::all(Val::Px(20.0)),
+                        margin: UiRect::right(Val::Px(20.0)),
+                        ..default()
+                    },
+                    background_color: theme.panel_color.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(text(
+                        player.name.clone(),
+                        theme.label_font_size,
+                        theme.title_color,
+                    ));
+                    for line in [
+                        format!("Health {}/{}", player.health, player.stats.max_health),
+                        format!("Attack {}", combatant.attack_pool()),
+                        format!("Defence {}", combatant.defense_pool()),
+                        format!("Gold {}", player.gold),
+                    ] {
+                        parent.spawn(text(line, theme.button_font_size_small, theme.text_color));
+                    }
+
+                    for slot in SLOTS {
+                        if !player.equipment.contains_key(&slot) {
+                            parent.spawn(text(
+                                format!("{}: empty", slot.name()),
+                                theme.button_font_size_small,
+                                theme.text_color,
+                            ));
+                        }
+                    }
+                    parent.spawn(text(
+                        format!(
+                            "Backpack {}/{}",
+                            player.inventory.len(),
+                            state.data.items.inventory_slots
+                        ),
+                        theme.button_font_size_small,
+                        theme.title_color,
+                    ));
+                    for (index, (_, entry)) in entries.iter().enumerate() {
+                        let color = if index == panel.index {
+                            theme.hovered_color
+                        } else {
+                            theme.text_color
+                        };
+                        parent.spawn(text(entry.clone(), theme.button_font_size_small, color));
+                    }
+                    parent.spawn(text(
+                        "Space to equip or use, I to close".into(),
+                        theme.button_font_size_small,
+                        theme.title_color,
+                    ));
+                });
+        });
+}
@@ -0,0 +1,248 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+
+use super::{camera::FocusTile, render, tokens::Token, OnBoardScene};
+use crate::{
+    board::{Board, TileKind},
+    game::{Config, SafeArea, Theme},
+    rules::MatchState,
+};
+
+const MAP_SIZE: Vec2 = Vec2::new(360., 220.);
+const MAP_PADDING: f32 = 12.;
+const TILE_DOT: f32 = 8.;
+const PLACE_DOT: f32 = 13.;
+const TOKEN_DOT: f32 = 10.;
+const MONSTER_DOT: f32 = 7.;
+const PATH_DOT: f32 = 3.;
+// map units between the dots drawing a path
+const PATH_SPACING: f32 = 7.;
+const PATH_COLOR: Color = Color::rgb(0.45, 0.4, 0.35);
+const MONSTER_COLOR: Color = Color::rgb(0.15, 0.05, 0.05);
+
+#[derive(Component)]
+pub struct Minimap;
+
+#[derive(Component)]
+pub struct MinimapToken {
+    seat: usize,
+}
+
+#[derive(Component)]
+pub struct MinimapMonster;
+
+// fits the board into the map keeping its shape, y grows down the map
+struct Projection {
+    bounds: Rect,
+    scale: f32,
+    offset: Vec2,
+}
+
+impl Projection {
+    fn new(board: &Board) -> Self {
+        let bounds = render::board_bounds(board);
+        let room = MAP_SIZE - Vec2::splat(MAP_PADDING * 2.);
+        let scale = (room / bounds.size()).min_element();
+        let offset = (MAP_SIZE - bounds.size() * scale) / 2.;
+        Self {
+            bounds,
+            scale,
+            offset,
+        }
+    }
+
+    fn to_map(&self, world: Vec2) -> Vec2 {
+        Vec2::new(
+            (world.x - self.bounds.min.x) * self.scale,
+            (self.bounds.max.y - world.y) * self.scale,
+        ) + self.offset
+    }
+
+    fn to_world(&self, map: Vec2) -> Vec2 {
+        let map = (map - self.offset) / self.scale;
+        Vec2::new(self.bounds.min.x + map.x, self.bounds.max.y - map.y)
+    }
+}
+
+fn dot(center: Vec2, size: f32, color: Color) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(center.x - size / 2.),
+                top: Val::Px(center.y - size / 2.),
+                ..default()
+            },
+            size: Size::new(Val::Px(size), Val::Px(size)),
+            ..default()
+        },
+        background_color: color.into(),
+        ..default()
+    }
+}
+
+// the board drawn from its tiles and paths in a corner, places that matter drawn bigger
+pub fn spawn_minimap(commands: &mut Commands, state: &MatchState, config: &Config, theme: &Theme) {
+    let board = &state.board;
+    let projection = Projection::new(board);
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    justify_content: JustifyContent::FlexEnd,
+                    align_items: AlignItems::FlexEnd,
+                    ..default()
+                },
+                ..default()
+            },
+            SafeArea,
+            OnBoardScene,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(MAP_SIZE.x), Val::Px(MAP_SIZE.y)),
+                            margin: UiRect::all(Val::Px(20.0)),
+                            ..default()
+                        },
+                        background_color: theme.normal_color.into(),
+                        ..default()
+                    },
+                    Interaction::default(),
+                    Minimap,
+                ))
+                .with_children(|parent| {
+                    for tile in board.tiles() {
+                        let from = projection.to_map(render::tile_position(board, tile.id));
+                        for next in &tile.next {
+                            let to = projection.to_map(render::tile_position(board, *next));
+                            let dots = ((to - from).length() / PATH_SPACING).floor() as usize;
+                            for step in 1..dots {
+                                let along = from.lerp(to, step as f32 / dots as f32);
+                                parent.spawn(dot(along, PATH_DOT, PATH_COLOR));
+                            }
+                        }
+                    }
+                    for tile in board.tiles() {
+                        let size = match tile.kind {
+                            TileKind::Start
+                            | TileKind::Town
+                            | TileKind::Shrine
+                            | TileKind::Rune => PLACE_DOT,
+                            TileKind::Battle | TileKind::Event | TileKind::Shortcut => TILE_DOT,
+                        };
+                        let center = projection.to_map(render::tile_position(board, tile.id));
+                        parent.spawn(dot(center, size, render::tile_color(tile.kind)));
+                    }
+                    for player in &state.players {
+                        let center = projection.to_map(render::tile_position(board, player.tile));
+                        parent.spawn((
+                            dot(center, TOKEN_DOT, config.palette.player_color(player.seat)),
+                            MinimapToken { seat: player.seat },
+                        ));
+                    }
+                });
+        });
+}
+
+// the tokens on the map follow the ones on the board, hops included
+pub fn update_tokens(
+    state: Res<MatchState>,
+    q_tokens: Query<(&Token, &Transform), Changed<Transform>>,
+    mut q_map_tokens: Query<(&MinimapToken, &mut Style)>,
+) {
+    let projection = Projection::new(&state.board);
+    for (token, transform) in &q_tokens {
+        let center = projection.to_map(transform.translation.truncate());
+        for (map_token, mut style) in &mut q_map_tokens {
+            if map_token.seat == token.seat {
+                style.position.left = Val::Px(center.x - TOKEN_DOT / 2.);
+                style.position.top = Val::Px(center.y - TOKEN_DOT / 2.);
+            }
+        }
+    }
+}
+
+pub fn update_monsters(
+    mut commands: Commands,
+    state: Res<MatchState>,
+    q_map: Query<Entity, With<Minimap>>,
+    q_new_map: Query<(), Added<Minimap>>,
+    q_monsters: Query<Entity, With<MinimapMonster>>,
+) {
+    if !state.is_changed() && q_new_map.is_empty() {
+        return;
+    }
+    let Ok(map) = q_map.get_single() else {
+        return;
+    };
+    for entity in &q_monsters {
+        commands.entity(entity).despawn_recursive();
+    }
+    let projection = Projection::new(&state.board);
+    commands.entity(map).with_children(|parent| {
+        for monster in &state.monsters {
+            let center = projection.to_map(render::tile_position(&state.board, monster.tile));
+            parent.spawn((dot(center, MONSTER_DOT, MONSTER_COLOR), MinimapMonster));
+        }
+    });
+}
+
+// pressing on the map moves the camera to the tile nearest to the pointer, dragging keeps it
+// moving along
+pub fn click_minimap(
+    state: Res<MatchState>,
+    windows: Res<Windows>,
+    q_map: Query<(&Interaction, &Node, &GlobalTransform), With<Minimap>>,
+    mut ev_focus: EventWriter<FocusTile>,
+) {
+    let Ok((interaction, node, transform)) = q_map.get_single() else {
+        return;
+    };
+    if *interaction != Interaction::Clicked {
+        return;
+    }
+    let Some(window) = windows.get_primary() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    // the cursor counts up from the bottom of the window, the ui down from the top
+    let cursor = Vec2::new(cursor.x, window.height() - cursor.y);
+    let corner = transform.translation().truncate() - node.size() / 2.;
+    let on_map = (cursor - corner) / node.size() * MAP_SIZE;
+    let world = Projection::new(&state.board).to_world(on_map);
+    let nearest = state.board.tiles().iter().min_by(|a, b| {
+        let a = render::tile_position(&state.board, a.id).distance_squared(world);
+        let b = render::tile_position(&state.board, b.id).distance_squared(world);
+        a.total_cmp(&b)
+    });
+    if let Some(tile) = nearest {
+        ev_focus.send(FocusTile(tile.id));
+    }
+}
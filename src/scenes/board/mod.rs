@@ -0,0 +1,384 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::{prelude::*, reflect::TypeUuid};
+use bevy_common_assets::ron::RonAssetPlugin;
+use rand::RngCore;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use super::{battle::BattleOutcome, clear_scene, loading::BoardGenerator, results::MatchResults};
+use crate::{
+    board::Board,
+    game::{
+        data_dir, last_replay, Announcement, Assets, BoardChoice, Config, DataFiles, State, Theme,
+    },
+    rng::GameRng,
+    rules::{Command, GameEvent, History, MatchState, Phase, Replay},
+};
+
+mod camera;
+mod cards;
+mod computer;
+mod game_log;
+mod hud;
+mod input;
+mod inventory;
+mod minimap;
+mod monsters;
+mod odds;
+mod render;
+mod replay;
+mod save;
+mod shop;
+mod tokens;
+mod undo;
+
+pub use computer::Computers;
+pub use replay::Playback;
+pub use save::SavedMatch;
+
+#[derive(Debug, Deserialize, TypeUuid)]
+#[serde(transparent)]
+#[uuid = "9a3e2b61-7c4d-4f0e-8b59-2d6a1c7e5f38"]
+pub struct BoardMap(pub Board);
+
+#[derive(Resource, Debug, Clone)]
+pub struct RandomBoard(pub Board);
+
+pub struct BoardScene;
+
+impl Plugin for BoardScene {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(RonAssetPlugin::<BoardMap>::new(&["board.ron"]))
+            .add_event::<Command>()
+            .add_event::<GameEvent>()
+            .add_event::<undo::Undone>()
+            .add_event::<camera::FocusTile>()
+            .add_system_set(SystemSet::on_enter(State::Board).with_system(setup))
+            .add_system_set(
+                SystemSet::on_update(State::Board)
+                    .with_system(apply_commands)
+                    .with_system(computer::think.before(apply_commands))
+                    .with_system(replay::playback_input.before(replay::play_back))
+                    .with_system(replay::play_back.before(apply_commands))
+                    .with_system(replay::update_status.after(apply_commands))
+                    .with_system(announce_events.after(apply_commands))
+                    .with_system(tokens::move_tokens.after(apply_commands))
+                    .with_system(tokens::token_arrived)
+                    .with_system(undo::undo.before(apply_commands))
+                    .with_system(tokens::snap_tokens.after(undo::undo))
+                    .with_system(monsters::update_monsters)
+                    .with_system(
+                        input::roll
+                            .after(inventory::toggle_inventory)
+                            .before(apply_commands),
+                    )
+                    .with_system(input::cast.after(inventory::toggle_inventory))
+                    .with_system(input::flee.before(apply_commands))
+                    .with_system(inventory::toggle_inventory)
+                    .with_system(inventory::navigate_inventory.after(inventory::toggle_inventory))
+                    .with_system(inventory::show_inventory.after(apply_commands))
+                    .with_system(shop::shop_input.before(apply_commands))
+                    .with_system(shop::shop_opened.after(apply_commands))
+                    .with_system(shop::show_shop.after(shop::shop_opened))
+                    .with_system(cards::show_card.after(apply_commands))
+                    .with_system(cards::dismiss_card)
+                    .with_system(input::show_fork)
+                    .with_system(input::choose_fork.after(input::show_fork))
+                    .with_system(input::update_hint)
+                    .with_system(odds::preview_move.after(apply_commands))
+                    .with_system(hud::track_roll.after(apply_commands))
+                    .with_system(game_log::record_events.after(apply_commands))
+                    .with_system(game_log::log_input)
+                    .with_system(game_log::show_log.after(game_log::record_events))
+                    .with_system(camera::camera_input)
+                    .with_system(camera::focus_tile.after(camera::camera_input))
+                    .with_system(minimap::click_minimap.before(camera::focus_tile))
+                    .with_system(minimap::update_tokens.after(tokens::move_tokens))
+                    .with_system(minimap::update_monsters.after(apply_commands))
+                    .with_system(camera::follow_player.after(apply_commands))
+                    .with_system(
+                        camera::move_camera
+                            .after(camera::follow_player)
+                            .after(camera::focus_tile)
+                            .after(tokens::move_tokens),
+                    )
+                    .with_system(hud::show_hud.after(hud::track_roll))
+                    .with_system(finish_match.after(apply_commands))
+                    .with_system(save::save_match.after(apply_commands)),
+            )
+            .add_system_set(SystemSet::on_resume(State::Board).with_system(battle_over))
+            .add_system_set(
+                SystemSet::on_exit(State::Board)
+                    .with_system(clear_scene::<OnBoardScene>)
+                    .with_system(camera::reset_camera)
+                    .with_system(exit),
+            );
+    }
+}
+
+#[derive(Component, Clone)]
+struct OnBoardScene;
+
+use bevy::asset::Assets as BevyAssets;
+use bevy::prelude::State as BevyState;
+#[allow(clippy::too_many_arguments)]
+fn setup(
+    mut commands: Commands,
+    assets: Res<Assets>,
+    maps: Res<BevyAssets<BoardMap>>,
+    data_files: DataFiles,
+    random_board: Option<Res<RandomBoard>>,
+    config: Res<Config>,
+    theme: Res<Theme>,
+    mut rng: ResMut<GameRng>,
+    playback: Option<Res<Playback>>,
+    saved: Option<Res<SavedMatch>>,
+    mut game_state: ResMut<BevyState<State>>,
+    mut ev_announcement: EventWriter<Announcement>,
+    mut ev_game: EventWriter<GameEvent>,
+) {
+    let (state, computers, replay) = match (&playback, &saved) {
+        (Some(playback), _) => {
+            let state = playback.replay().initial_state();
+            let computers = computer::Computers::watching(state.players.len());
+            let replay = Replay::start(&state);
+            (state, computers, replay)
+        }
+        // a saved match plays its commands again to be where it was left
+        (None, Some(saved)) => {
+            commands.remove_resource::<SavedMatch>();
+            match saved.replay.verify() {
+                Ok(state) => {
+                    let computers = computer::Computers::new(&saved.seats, state.rng.seed());
+                    (state, computers, saved.replay.clone())
+                }
+                Err(err) => {
+                    error!("can't continue the saved match: {}", err);
+                    game_state
+                        .set(State::Menu)
+                        .expect("Failed to set game state");
+                    return;
+                }
+            }
+        }
+        (None, None) => {
+            let board = match (config.board, random_board) {
+                // take it so the next match gets its own, the next one is generated meanwhile
+                (BoardChoice::Random, Some(random_board)) => {
+                    commands.remove_resource::<RandomBoard>();
+                    commands.insert_resource(BoardGenerator::spawn(&mut rng));
+                    random_board.0.clone()
+                }
+                (BoardChoice::Random, None) => {
+                    error!("the random board is not ready");
+                    game_state
+                        .set(State::Menu)
+                        .expect("Failed to set game state");
+                    return;
+                }
+                (BoardChoice::Classic, _) => maps
+                    .get(&assets.classic_board)
+                    .expect("board should be loaded before the board scene")
+                    .0
+                    .clone(),
+            };
+            if let Err(err) = board.validate() {
+                error!("invalid board {:?}: {}", board.name, err);
+                game_state
+                    .set(State::Menu)
+                    .expect("Failed to set game state");
+                return;
+            }
+
+            let data = match data_files.game_data() {
+                Ok(data) => Arc::new(data),
+                Err(err) => {
+                    error!("invalid game data: {}", err);
+                    game_state
+                        .set(State::Menu)
+                        .expect("Failed to set game state");
+                    return;
+                }
+            };
+
+            let names = config
+                .seats
+                .iter()
+                .enumerate()
+                .map(|(seat, controller)| controller.player_name(seat))
+                .collect::<Vec<_>>();
+            let state = MatchState::new(board, data, config.rules, &names, rng.next_u64());
+            let computers = computer::Computers::new(&config.seats, state.rng.seed());
+            let replay = Replay::start(&state);
+            (state, computers, replay)
+        }
+    };
+
+    render::spawn_board(&mut commands, &state.board, &assets, OnBoardScene);
+    ev_announcement.send(Announcement::scene(format!(
+        "{} board, {} tiles",
+        state.board.name,
+        state.board.tiles().len()
+    )));
+
+    commands.insert_resource(computers);
+    tokens::spawn_tokens(&mut commands, &state, &config);
+    minimap::spawn_minimap(&mut commands, &state, &config, &theme);
+    input::spawn_hint(&mut commands, &assets, &theme);
+    if playback.is_some() {
+        replay::spawn_status(&mut commands, &assets, &theme);
+    }
+    commands.insert_resource(tokens::TokenAnimations::default());
+    commands.insert_resource(hud::LastRoll::default());
+    commands.insert_resource(game_log::GameLog::default());
+    commands.insert_resource(camera::BoardCamera::default());
+    commands.insert_resource(input::ForkSelection::default());
+    commands.insert_resource(inventory::InventoryPanel::default());
+    commands.insert_resource(shop::ShopSelection::default());
+    ev_game.send_batch(state.start_events());
+    commands.insert_resource(History::default());
+    commands.insert_resource(replay);
+    commands.insert_resource(state);
+}
+
+// every match played is kept as a replay and a log, watching one again doesn't overwrite them
+fn exit(
+    mut commands: Commands,
+    replay: Option<Res<Replay>>,
+    log: Option<Res<game_log::GameLog>>,
+    playback: Option<Res<Playback>>,
+) {
+    if let (Some(replay), None) = (replay, playback) {
+        if !replay.commands.is_empty() {
+            let folder = data_dir();
+            let path = folder
+                .join("replays")
+                .join(format!("match-{}.replay.ron", replay.seed));
+            for path in [path, last_replay()] {
+                if let Err(err) = replay.save(path) {
+                    warn!("replay not saved: {}", err);
+                }
+            }
+            if let Some(log) = log {
+                if let Err(err) = log.save(
+                    folder
+                        .join("logs")
+                        .join(format!("match-{}.log", replay.seed)),
+                ) {
+                    warn!("game log not saved: {}", err);
+                }
+            }
+        }
+    }
+    commands.remove_resource::<MatchState>();
+    commands.remove_resource::<computer::Computers>();
+    commands.remove_resource::<hud::LastRoll>();
+    commands.remove_resource::<game_log::GameLog>();
+    commands.remove_resource::<camera::BoardCamera>();
+    commands.remove_resource::<History>();
+    commands.remove_resource::<Replay>();
+    commands.remove_resource::<Playback>();
+}
+
+fn apply_commands(
+    mut ev_command: EventReader<Command>,
+    mut state: ResMut<MatchState>,
+    mut history: ResMut<History>,
+    mut replay: ResMut<Replay>,
+    mut game_state: ResMut<BevyState<State>>,
+    mut ev_game: EventWriter<GameEvent>,
+) {
+    for command in ev_command.iter() {
+        // the battle scene fights it, so the dice can be shown as they roll
+        if *command == Command::Fight && matches!(state.phase, Phase::Battle { .. }) {
+            game_state
+                .push(State::Battle)
+                .expect("Failed to push game state");
+            break;
+        }
+        match history.apply(&mut state, command.clone()) {
+            Ok(events) => {
+                replay.record(command.clone(), &state);
+                ev_game.send_batch(events);
+            }
+            Err(err) => warn!("command {:?} rejected: {}", command, err),
+        }
+    }
+}
+
+// the results take over once the last move has played out
+fn finish_match(
+    mut commands: Commands,
+    state: Res<MatchState>,
+    animations: Res<tokens::TokenAnimations>,
+    mut game_state: ResMut<BevyState<State>>,
+) {
+    let Some(winner) = state.winner() else {
+        return;
+    };
+    if !animations.is_idle() {
+        return;
+    }
+    commands.insert_resource(MatchResults {
+        victory: state.rules.victory,
+        winner: state.players[winner].name.clone(),
+        turn: state.turn,
+        standings: state.standings(),
+    });
+    game_state
+        .set(State::Results)
+        .expect("Failed to set game state");
+}
+
+fn battle_over(
+    mut commands: Commands,
+    outcome: Option<Res<BattleOutcome>>,
+    mut ev_game: EventWriter<GameEvent>,
+) {
+    if let Some(outcome) = outcome {
+        ev_game.send_batch(outcome.events.clone());
+        commands.remove_resource::<BattleOutcome>();
+    }
+}
+
+fn announce_events(
+    mut ev_game: EventReader<GameEvent>,
+    state: Res<MatchState>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    for event in ev_game.iter() {
+        // the tokens, the shop and the battle scene already show these
+        if matches!(
+            event,
+            GameEvent::Moved { .. }
+                | GameEvent::MonsterMoved { .. }
+                | GameEvent::ShopOpened { .. }
+                | GameEvent::BattleResolved(_)
+        ) {
+            continue;
+        }
+        ev_announcement.send(Announcement::game(game_log::describe(event, &state)));
+    }
+}
@@ -0,0 +1,118 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+
+use super::{render, OnBoardScene};
+use crate::{
+    board::TileId,
+    game::Assets,
+    rules::{Fighter, GameEvent, MatchState},
+};
+
+const MONSTER_SIZE: f32 = 56.0;
+const MONSTER_Z: f32 = 4.0;
+const LABEL_FONT_SIZE: f32 = 36.0;
+const MONSTER_COLOR: Color = Color::rgb(0.25, 0.1, 0.1);
+const LABEL_COLOR: Color = Color::WHITE;
+
+#[derive(Component)]
+pub struct MonsterToken {
+    id: u32,
+}
+
+fn monster_position(state: &MatchState, tile: TileId) -> Vec3 {
+    render::tile_position(&state.board, tile).extend(MONSTER_Z)
+}
+
+pub fn update_monsters(
+    mut commands: Commands,
+    mut ev_game: EventReader<GameEvent>,
+    state: Res<MatchState>,
+    assets: Res<Assets>,
+    mut q_monsters: Query<(Entity, &MonsterToken, &mut Transform)>,
+) {
+    for event in ev_game.iter() {
+        match event {
+            GameEvent::MonsterSpawned {
+                monster,
+                kind,
+                tile,
+            } => {
+                let name = state
+                    .data
+                    .monsters
+                    .monster(kind)
+                    .map_or(kind.as_str(), |def| def.name.as_str());
+                commands
+                    .spawn((
+                        SpriteBundle {
+                            sprite: Sprite {
+                                color: MONSTER_COLOR,
+                                custom_size: Some(Vec2::splat(MONSTER_SIZE)),
+                                ..default()
+                            },
+                            transform: Transform::from_translation(monster_position(&state, *tile)),
+                            ..default()
+                        },
+                        MonsterToken { id: *monster },
+                        Name::new(name.to_string()),
+                        OnBoardScene,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(Text2dBundle {
+                            text: Text::from_section(
+                                name.chars().next().unwrap_or('?').to_string(),
+                                TextStyle {
+                                    font: assets.default_font.clone(),
+                                    font_size: LABEL_FONT_SIZE,
+                                    color: LABEL_COLOR,
+                                },
+                            )
+                            .with_alignment(TextAlignment::CENTER),
+                            transform: Transform::from_xyz(0., 0., 0.1),
+                            ..default()
+                        });
+                    });
+            }
+            GameEvent::MonsterMoved { monster, tile } => {
+                for (_, token, mut transform) in q_monsters.iter_mut() {
+                    if token.id == *monster {
+                        transform.translation = monster_position(&state, *tile);
+                    }
+                }
+            }
+            GameEvent::Defeated {
+                fighter: Fighter::Monster(monster),
+                ..
+            } => {
+                for (entity, token, _) in q_monsters.iter() {
+                    if token.id == *monster {
+                        commands.entity(entity).despawn_recursive();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
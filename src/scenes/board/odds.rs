@@ -0,0 +1,97 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+
+use super::{computer::Computers, render, tokens::TokenAnimations, OnBoardScene};
+use crate::{
+    ai::reach_chances,
+    dice::percent,
+    game::{Assets, Config},
+    rules::{odds, Fighter, MatchState, Phase},
+};
+
+const ODDS_Z: f32 = 6.0;
+const ODDS_FONT_SIZE: f32 = 26.0;
+const ODDS_COLOR: Color = Color::WHITE;
+const ODDS_OFFSET: f32 = render::TILE_SIZE / 2. + 18.;
+
+#[derive(Component)]
+pub struct OddsLabel;
+
+// before a human rolls, every tile the dice can reach shows the chance of getting there and
+// of winning the fight waiting on it
+pub fn preview_move(
+    mut commands: Commands,
+    config: Res<Config>,
+    state: Res<MatchState>,
+    animations: Res<TokenAnimations>,
+    computers: Res<Computers>,
+    assets: Res<Assets>,
+    q_labels: Query<Entity, With<OddsLabel>>,
+) {
+    let showing = config.show_odds
+        && matches!(state.phase, Phase::Roll)
+        && animations.is_idle()
+        && computers.human_turn(&state);
+    if showing && !state.is_changed() && !q_labels.is_empty() {
+        return;
+    }
+    for entity in &q_labels {
+        commands.entity(entity).despawn_recursive();
+    }
+    if !showing {
+        return;
+    }
+
+    let seat = state.current;
+    let player = state.current_player();
+    let attacker = state.combatant(Fighter::Player(seat));
+    for (tile, chance) in reach_chances(&state.board, player.tile, state.movement_pool()) {
+        let mut label = percent(chance);
+        if let Some(defender) = state.defender_at(seat, tile) {
+            if tile != state.board.start() {
+                let odds = odds(&attacker, &state.combatant(defender));
+                label = format!("{}\nwin {}", label, percent(odds.attacker_wins));
+            }
+        }
+        let position = render::tile_position(&state.board, tile) + Vec2::Y * ODDS_OFFSET;
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    label,
+                    TextStyle {
+                        font: assets.default_font.clone(),
+                        font_size: ODDS_FONT_SIZE,
+                        color: ODDS_COLOR,
+                    },
+                )
+                .with_alignment(TextAlignment::CENTER),
+                transform: Transform::from_translation(position.extend(ODDS_Z)),
+                ..default()
+            },
+            OddsLabel,
+            OnBoardScene,
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+
+use crate::{
+    board::{Board, TileId, TileKind},
+    game::Assets,
+};
+
+pub const TILE_SIZE: f32 = 90.0;
+const TILE_Z: f32 = 1.0;
+const LABEL_Z: f32 = 2.0;
+const CONNECTION_Z: f32 = 0.0;
+const CONNECTION_WIDTH: f32 = 12.0;
+const CONNECTION_COLOR: Color = Color::rgb(0.45, 0.4, 0.35);
+const LABEL_FONT_SIZE: f32 = 40.0;
+const LABEL_COLOR: Color = Color::BLACK;
+
+pub fn tile_color(kind: TileKind) -> Color {
+    match kind {
+        TileKind::Start => Color::rgb(0.95, 0.95, 0.95),
+        TileKind::Town => Color::rgb(0.85, 0.65, 0.3),
+        TileKind::Shrine => Color::rgb(0.6, 0.8, 0.95),
+        TileKind::Battle => Color::rgb(0.8, 0.3, 0.3),
+        TileKind::Rune => Color::rgb(0.65, 0.45, 0.85),
+        TileKind::Event => Color::rgb(0.4, 0.75, 0.4),
+        TileKind::Shortcut => Color::rgb(0.9, 0.85, 0.35),
+    }
+}
+
+fn tile_label(kind: TileKind) -> &'static str {
+    match kind {
+        TileKind::Start => "S",
+        TileKind::Town => "T",
+        TileKind::Shrine => "+",
+        TileKind::Battle => "B",
+        TileKind::Rune => "R",
+        TileKind::Event => "?",
+        TileKind::Shortcut => ">",
+    }
+}
+
+pub fn tile_position(board: &Board, id: TileId) -> Vec2 {
+    let (x, y) = board.tile(id).position;
+    Vec2::new(x, y)
+}
+
+// the tiles with room for a tile around them
+pub fn board_bounds(board: &Board) -> Rect {
+    let mut bounds = Rect::from_center_size(tile_position(board, board.start()), Vec2::ZERO);
+    for tile in board.tiles() {
+        bounds = bounds.union_point(tile_position(board, tile.id));
+    }
+    bounds.inset(TILE_SIZE)
+}
+
+pub fn spawn_board(
+    commands: &mut Commands,
+    board: &Board,
+    assets: &Assets,
+    marker: impl Component + Clone,
+) {
+    for tile in board.tiles() {
+        let position = tile_position(board, tile.id);
+        for next in &tile.next {
+            let to = tile_position(board, *next);
+            let delta = to - position;
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: CONNECTION_COLOR,
+                        custom_size: Some(Vec2::new(delta.length(), CONNECTION_WIDTH)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(
+                        ((position + to) / 2.).extend(CONNECTION_Z),
+                    )
+                    .with_rotation(Quat::from_rotation_z(delta.y.atan2(delta.x))),
+                    ..default()
+                },
+                marker.clone(),
+            ));
+        }
+
+        commands
+            .spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: tile_color(tile.kind),
+                        custom_size: Some(Vec2::splat(TILE_SIZE)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(position.extend(TILE_Z)),
+                    ..default()
+                },
+                Name::new(format!("{} {}", tile.kind.name(), tile.id)),
+                marker.clone(),
+            ))
+            .with_children(|parent| {
+                parent.spawn(Text2dBundle {
+                    text: Text::from_section(
+                        tile_label(tile.kind),
+                        TextStyle {
+                            font: assets.default_font.clone(),
+                            font_size: LABEL_FONT_SIZE,
+                            color: LABEL_COLOR,
+                        },
+                    )
+                    .with_alignment(TextAlignment::CENTER),
+                    transform: Transform::from_xyz(0., 0., LABEL_Z - TILE_Z),
+                    ..default()
+                });
+            });
+    }
+}
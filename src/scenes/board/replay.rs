@@ -0,0 +1,212 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+
+use super::{tokens::TokenAnimations, OnBoardScene};
+use crate::{
+    game::{Announcement, Assets, SafeArea, Theme},
+    rules::{Command, Replay},
+};
+
+// how long each recorded command waits at normal speed
+const STEP_SECONDS: f32 = 0.8;
+const SPEEDS: [f32; 5] = [0.25, 0.5, 1., 2., 4.];
+const NORMAL_SPEED: usize = 2;
+
+// a recorded match being watched, the commands go through the rules again as if played
+#[derive(Resource)]
+pub struct Playback {
+    replay: Replay,
+    paused: bool,
+    speed: usize,
+    step: bool,
+    sent: Option<usize>,
+    finished: bool,
+    timer: Timer,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            paused: false,
+            speed: NORMAL_SPEED,
+            step: false,
+            sent: None,
+            finished: false,
+            timer: Timer::from_seconds(STEP_SECONDS, TimerMode::Repeating),
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+}
+
+#[derive(Component)]
+pub struct PlaybackStatus;
+
+pub fn spawn_status(commands: &mut Commands, assets: &Assets, theme: &Theme) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::FlexEnd,
+                    ..default()
+                },
+                ..default()
+            },
+            SafeArea,
+            OnBoardScene,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.default_font.clone(),
+                        font_size: theme.label_font_size,
+                        color: theme.title_color,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                }),
+                PlaybackStatus,
+            ));
+        });
+}
+
+// space pauses, right steps one command while paused, up and down change the speed
+pub fn playback_input(
+    playback: Option<ResMut<Playback>>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+    let pressed = |key: KeyCode, button: GamepadButtonType| {
+        keyboard.just_pressed(key)
+            || gamepads
+                .iter()
+                .any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button)))
+    };
+    if pressed(KeyCode::Space, GamepadButtonType::South) {
+        playback.paused = !playback.paused;
+        playback.timer.reset();
+    }
+    if pressed(KeyCode::Right, GamepadButtonType::DPadRight) && playback.paused {
+        playback.step = true;
+    }
+    if pressed(KeyCode::Up, GamepadButtonType::DPadUp) {
+        playback.speed = (playback.speed + 1).min(SPEEDS.len() - 1);
+    }
+    if pressed(KeyCode::Down, GamepadButtonType::DPadDown) {
+        playback.speed = playback.speed.saturating_sub(1);
+    }
+}
+
+// the next recorded command goes out once the board is still, a fight takes the battle
+// scene out as it does when played, and at the end the match has to land on the same
+// checksum the recording did
+pub fn play_back(
+    time: Res<Time>,
+    animations: Res<TokenAnimations>,
+    recording: Res<Replay>,
+    playback: Option<ResMut<Playback>>,
+    mut ev_command: EventWriter<Command>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+    if playback.finished {
+        return;
+    }
+    let next = recording.commands.len();
+    let rejected = playback.sent == Some(next);
+    if rejected || next >= playback.replay.commands.len() {
+        playback.finished = true;
+        let text = if rejected {
+            format!("The replay went off course at command {}", next + 1)
+        } else if recording.checksum == playback.replay.checksum {
+            "The replay played out as recorded".to_string()
+        } else {
+            "The replay ended somewhere else than recorded".to_string()
+        };
+        ev_announcement.send(Announcement::game(text));
+        return;
+    }
+    if !animations.is_idle() {
+        return;
+    }
+    if playback.paused {
+        if !playback.step {
+            return;
+        }
+        playback.step = false;
+    } else {
+        let delta = time.delta().mul_f32(SPEEDS[playback.speed]);
+        if !playback.timer.tick(delta).just_finished() {
+            return;
+        }
+    }
+    playback.sent = Some(next);
+    ev_command.send(playback.replay.commands[next].clone());
+}
+
+pub fn update_status(
+    playback: Option<Res<Playback>>,
+    recording: Res<Replay>,
+    mut q_status: Query<&mut Text, With<PlaybackStatus>>,
+) {
+    let Some(playback) = playback else {
+        return;
+    };
+    if !playback.is_changed() && !recording.is_changed() {
+        return;
+    }
+    let progress = format!(
+        "Replay {}/{}",
+        recording.commands.len(),
+        playback.replay.commands.len()
+    );
+    let status = if playback.finished {
+        format!("{}, finished", progress)
+    } else if playback.paused {
+        format!("{}, paused, Space to play, Right to step", progress)
+    } else {
+        format!(
+            "{}, {}x, Space to pause, Up and Down for the speed",
+            progress, SPEEDS[playback.speed]
+        )
+    };
+    for mut text in &mut q_status {
+        text.sections[0].value = status.clone();
+    }
+}
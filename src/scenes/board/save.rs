@@ -0,0 +1,66 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+use serde::{Deserialize, Serialize};
+
+use super::{computer::Computers, replay::Playback};
+use crate::{
+    game::{Controller, SAVE_KEY},
+    rules::{MatchState, Replay},
+};
+
+// a match left halfway, the replay so far is enough to get back to where it was
+#[derive(Resource, Debug, Clone, Deserialize, Serialize)]
+pub struct SavedMatch {
+    pub seats: Vec<Controller>,
+    pub replay: Replay,
+}
+
+impl SavedMatch {
+    pub fn load(store: &PkvStore) -> Option<Self> {
+        store.get::<Option<Self>>(SAVE_KEY).ok().flatten()
+    }
+}
+
+// saved after every command so quitting any time can be continued, a finished match
+// leaves nothing to continue and a watched one never touches the save
+pub fn save_match(
+    state: Res<MatchState>,
+    replay: Res<Replay>,
+    computers: Res<Computers>,
+    playback: Option<Res<Playback>>,
+    mut store: ResMut<PkvStore>,
+) {
+    if playback.is_some() || !replay.is_changed() {
+        return;
+    }
+    let saved = state.winner().is_none().then(|| SavedMatch {
+        seats: computers.controllers().to_vec(),
+        replay: replay.clone(),
+    });
+    if let Err(err) = store.set(SAVE_KEY, &saved) {
+        warn!("match not saved: {}", err);
+    }
+}
@@ -0,0 +1,247 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+
+use super::{super::confirm_pressed, computer::Computers, tokens::TokenAnimations, OnBoardScene};
+use crate::{
+    board::TileId,
+    game::{Announcement, Assets, SafeArea, Theme},
+    rules::{Command, GameEvent, Goods, MatchState, Phase},
+};
+
+const PANEL_WIDTH: f32 = 640.0;
+
+#[derive(Resource, Default)]
+pub struct ShopSelection {
+    index: usize,
+}
+
+#[derive(Component)]
+pub struct ShopRoot;
+
+// every line of the shop that can be picked with the command it sends
+fn entries(state: &MatchState, tile: TileId) -> Vec<(Command, String)> {
+    let mut entries = Vec::new();
+    let player = state.current_player();
+    if let Some((shop, def)) = state.shop_at(tile) {
+        for (index, entry) in def.stock.iter().enumerate() {
+            let name = state.data.goods_name(&entry.goods);
+            let text = match (shop.quantities[index], state.shop_price(tile, index)) {
+                (0, _) => format!("{}, sold out", name),
+                (quantity, Some(price)) => {
+                    format!("Buy {} for {} gold, {} left", name, price, quantity)
+                }
+                (_, None) => continue,
+            };
+            entries.push((Command::Buy(index), text));
+        }
+    }
+    for (index, item) in player.inventory.iter().enumerate() {
+        let price = state.sell_price(&Goods::Item(item.clone()));
+        let name = state.data.items.name(item);
+        entries.push((
+            Command::SellItem(index),
+            format!("Sell {} for {} gold", name, price),
+        ));
+    }
+    for (rune, count) in &player.runes {
+        let goods = Goods::Rune(rune.clone());
+        entries.push((
+            Command::SellRune(rune.clone()),
+            format!(
+                "Sell {} for {} gold, {} owned",
+                state.data.goods_name(&goods),
+                state.sell_price(&goods),
+                count
+            ),
+        ));
+    }
+    entries.push((Command::LeaveShop, "Leave".into()));
+    entries
+}
+
+fn open_shop(state: &MatchState, animations: &TokenAnimations) -> Option<TileId> {
+    match state.phase {
+        Phase::Shop { tile } if animations.is_idle() => Some(tile),
+        _ => None,
+    }
+}
+
+pub fn shop_opened(
+    mut ev_game: EventReader<GameEvent>,
+    state: Res<MatchState>,
+    mut selection: ResMut<ShopSelection>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    for event in ev_game.iter() {
+        if let GameEvent::ShopOpened { tile, .. } = event {
+            selection.index = 0;
+            if let Some((_, def)) = state.shop_at(*tile) {
+                ev_announcement.send(Announcement::scene(format!(
+                    "{}, you have {} gold",
+                    def.name,
+                    state.current_player().gold
+                )));
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn shop_input(
+    state: Res<MatchState>,
+    animations: Res<TokenAnimations>,
+    computers: Res<Computers>,
+    mut selection: ResMut<ShopSelection>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut ev_command: EventWriter<Command>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    let Some(tile) = open_shop(&state, &animations) else {
+        return;
+    };
+    if !computers.human_turn(&state) {
+        return;
+    }
+    let entries = entries(&state, tile);
+    let count = entries.len();
+
+    let gamepad_pressed = |button_type| {
+        gamepads
+            .iter()
+            .any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+    };
+    let old_index = selection.index;
+    if keyboard.just_pressed(KeyCode::Up) || gamepad_pressed(GamepadButtonType::DPadUp) {
+        selection.index = (selection.index + count - 1) % count;
+    }
+    if keyboard.any_just_pressed([KeyCode::Down, KeyCode::Tab])
+        || gamepad_pressed(GamepadButtonType::DPadDown)
+    {
+        selection.index = (selection.index + 1) % count;
+    }
+    selection.index = selection.index.min(count - 1);
+    if selection.index != old_index {
+        ev_announcement.send(Announcement::focus(entries[selection.index].1.clone()));
+    }
+
+    if keyboard.just_pressed(KeyCode::Back) || gamepad_pressed(GamepadButtonType::East) {
+        ev_command.send(Command::LeaveShop);
+    } else if confirm_pressed(&keyboard, &gamepads, &buttons) {
+        ev_command.send(entries[selection.index].0.clone());
+    }
+}
+
+pub fn show_shop(
+    mut commands: Commands,
+    state: Res<MatchState>,
+    animations: Res<TokenAnimations>,
+    selection: Res<ShopSelection>,
+    assets: Res<Assets>,
+    theme: Res<Theme>,
+    q_root: Query<Entity, With<ShopRoot>>,
+) {
+    if !state.is_changed()
+        && !animations.is_changed()
+        && !selection.is_changed()
+        && !theme.is_changed()
+    {
+        return;
+    }
+    for entity in &q_root {
+        commands.entity(entity).despawn_recursive();
+    }
+    let Some(tile) = open_shop(&state, &animations) else {
+        return;
+    };
+    let Some((_, def)) = state.shop_at(tile) else {
+        return;
+    };
+
+    let player = state.current_player();
+    let text = |value: String, font_size: f32, color: Color| {
+        TextBundle::from_section(
+            value,
+            TextStyle {
+                font: assets.default_font.clone(),
+                font_size,
+                color,
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(4.0)),
+            ..default()
+        })
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            SafeArea,
+            ShopRoot,
+            OnBoardScene,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        size: Size::new(Val::Px(PANEL_WIDTH), Val::Auto),
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..default()
+                    },
+                    background_color: theme.panel_color.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(text(
+                        def.name.clone(),
+                        theme.label_font_size,
+                        theme.title_color,
+                    ));
+                    parent.spawn(text(
+                        format!("{} has {} gold", player.name, player.gold),
+                        theme.button_font_size_small,
+                        theme.text_color,
+                    ));
+                    for (index, (_, entry)) in entries(&state, tile).into_iter().enumerate() {
+                        let color = if index == selection.index {
+                            theme.hovered_color
+                        } else {
+                            theme.text_color
+                        };
+                        parent.spawn(text(entry, theme.button_font_size_small, color));
+                    }
+                });
+        });
+}
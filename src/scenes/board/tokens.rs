@@ -0,0 +1,172 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_tweening::{lens::TransformPositionLens, *};
+
+use super::{render, undo::Undone, OnBoardScene};
+use crate::{
+    board::TileId,
+    game::Config,
+    rules::{GameEvent, MatchState},
+};
+
+const TOKEN_SIZE: f32 = 36.0;
+const TOKEN_Z: f32 = 5.0;
+const TOKEN_OFFSETS: [Vec2; 4] = [
+    Vec2::new(-20., 20.),
+    Vec2::new(20., 20.),
+    Vec2::new(-20., -20.),
+    Vec2::new(20., -20.),
+];
+const HOP_MILLIS: u64 = 250;
+const TOKEN_ARRIVED: u64 = 1;
+
+#[derive(Component)]
+pub struct Token {
+    pub seat: usize,
+}
+
+#[derive(Resource, Default)]
+pub struct TokenAnimations {
+    moving: usize,
+}
+
+impl TokenAnimations {
+    pub fn is_idle(&self) -> bool {
+        self.moving == 0
+    }
+}
+
+pub fn token_position(state: &MatchState, seat: usize, tile: TileId) -> Vec3 {
+    (render::tile_position(&state.board, tile) + TOKEN_OFFSETS[seat % TOKEN_OFFSETS.len()])
+        .extend(TOKEN_Z + seat as f32 * 0.1)
+}
+
+pub fn spawn_tokens(commands: &mut Commands, state: &MatchState, config: &Config) {
+    for player in &state.players {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: config.palette.player_color(player.seat),
+                    custom_size: Some(Vec2::splat(TOKEN_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_translation(token_position(
+                    state,
+                    player.seat,
+                    player.tile,
+                )),
+                ..default()
+            },
+            Token { seat: player.seat },
+            Name::new(player.name.clone()),
+            OnBoardScene,
+        ));
+    }
+}
+
+pub fn move_tokens(
+    mut commands: Commands,
+    mut ev_game: EventReader<GameEvent>,
+    state: Res<MatchState>,
+    config: Res<Config>,
+    mut animations: ResMut<TokenAnimations>,
+    mut q_tokens: Query<(Entity, &Token, &mut Transform)>,
+) {
+    for event in ev_game.iter() {
+        let (seat, path) = match event {
+            GameEvent::Moved { seat, path } => (seat, path.clone()),
+            // a teleport is a single jump straight to the destination
+            GameEvent::Teleported { seat, tile } => (seat, vec![*tile]),
+            _ => continue,
+        };
+        let Some((entity, _, mut transform)) = q_tokens
+            .iter_mut()
+            .find(|(_, token, _)| token.seat == *seat)
+        else {
+            continue;
+        };
+        let positions = path
+            .iter()
+            .map(|tile| token_position(&state, *seat, *tile))
+            .collect::<Vec<_>>();
+
+        if config.reduced_motion {
+            if let Some(last) = positions.last() {
+                transform.translation = *last;
+            }
+            continue;
+        }
+
+        let mut start = transform.translation;
+        let mut hops = Vec::with_capacity(positions.len());
+        for (index, end) in positions.iter().enumerate() {
+            let hop = Tween::new(
+                EaseFunction::QuadraticInOut,
+                Duration::from_millis(HOP_MILLIS),
+                TransformPositionLens { start, end: *end },
+            );
+            hops.push(if index + 1 == positions.len() {
+                hop.with_completed_event(TOKEN_ARRIVED)
+            } else {
+                hop
+            });
+            start = *end;
+        }
+        if !hops.is_empty() {
+            commands
+                .entity(entity)
+                .insert(Animator::new(Sequence::new(hops)));
+            animations.moving += 1;
+        }
+    }
+}
+
+pub fn token_arrived(
+    mut ev_tween_completed: EventReader<TweenCompleted>,
+    mut animations: ResMut<TokenAnimations>,
+) {
+    for completed in ev_tween_completed.iter() {
+        if completed.user_data == TOKEN_ARRIVED {
+            animations.moving = animations.moving.saturating_sub(1);
+        }
+    }
+}
+
+// an undo puts the tokens straight back where the match has them
+pub fn snap_tokens(
+    mut ev_undone: EventReader<Undone>,
+    state: Res<MatchState>,
+    mut q_tokens: Query<(&Token, &mut Transform)>,
+) {
+    if ev_undone.iter().last().is_none() {
+        return;
+    }
+    for (token, mut transform) in &mut q_tokens {
+        let tile = state.players[token.seat].tile;
+        transform.translation = token_position(&state, token.seat, tile);
+    }
+}
@@ -0,0 +1,83 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+
+use super::{computer::Computers, game_log::GameLog, tokens::TokenAnimations};
+use crate::{
+    game::Announcement,
+    rules::{Command, History, MatchState, Replay},
+};
+
+// the match went back to before the last decision
+pub struct Undone;
+
+fn describe(command: &Command) -> &'static str {
+    match command {
+        Command::Move => "the move",
+        Command::Choose(_) => "the path",
+        Command::Buy(_) => "the purchase",
+        Command::SellItem(_) | Command::SellRune(_) => "the sale",
+        Command::Cast { .. } => "the spell",
+        Command::Equip(_) | Command::Unequip(_) => "the equipment change",
+        Command::UseItem(_) => "the item",
+        _ => "the last decision",
+    }
+}
+
+// z or the west button takes back the last decision of the turn, the dice stay as they fell
+#[allow(clippy::too_many_arguments)]
+pub fn undo(
+    mut state: ResMut<MatchState>,
+    animations: Res<TokenAnimations>,
+    computers: Res<Computers>,
+    mut history: ResMut<History>,
+    mut replay: ResMut<Replay>,
+    mut log: ResMut<GameLog>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut ev_undone: EventWriter<Undone>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    let pressed = keyboard.just_pressed(KeyCode::Z)
+        || gamepads.iter().any(|gamepad| {
+            buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::West))
+        });
+    if !pressed || !animations.is_idle() || !computers.human_turn(&state) {
+        return;
+    }
+    let Some(command) = history.undo(&mut state) else {
+        ev_announcement.send(Announcement::game("Nothing to take back"));
+        return;
+    };
+    replay.undo(&state);
+    ev_undone.send(Undone);
+    let text = format!(
+        "{} took back {}",
+        state.current_player().name,
+        describe(&command)
+    );
+    log.note(state.turn, text.clone());
+    ev_announcement.send(Announcement::game(text));
+}
@@ -1,65 +0,0 @@
-/***
-Copyright (c) 2022 Juan Medina
-
-Permission is hereby granted, free of charge, to any person obtaining
-a copy of this software and associated documentation files (the
-"Software"), to deal in the Software without restriction, including
-without limitation the rights to use, copy, modify, merge, publish,
-distribute, sublicense, and/or sell copies of the Software, and to
-permit persons to whom the Software is furnished to do so, subject to
-the following conditions:
-
-The above copyright notice and this permission notice shall be
-included in all copies or substantial portions of the Software.
-
-THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
-EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
-MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
-NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
-LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
-OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
-WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
-***/
-
-use crate::game::{Assets, State};
-use bevy::prelude::*;
-
-pub struct Hello;
-
-impl Plugin for Hello {
-    fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_enter(State::Hello).with_system(setup));
-    }
-}
-
-const FONT_SIZE: f32 = 80.0;
-const FONT_COLOR: Color = Color::WHITE;
-
-fn setup(mut commands: Commands, assets: Res<Assets>) {
-    commands
-        .spawn((NodeBundle {
-            style: Style {
-                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            ..default()
-        },))
-        .with_children(|parent| {
-            parent.spawn(
-                TextBundle::from_section(
-                    "Hello World!",
-                    TextStyle {
-                        font: assets.default_font.clone(),
-                        font_size: FONT_SIZE,
-                        color: FONT_COLOR,
-                    },
-                )
-                .with_style(Style {
-                    margin: UiRect::all(Val::Px(50.0)),
-                    ..default()
-                }),
-            );
-        });
-}
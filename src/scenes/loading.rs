@@ -21,27 +21,37 @@ OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 ***/
 
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc, Mutex,
+};
+
 use bevy::{
     prelude::*,
     render::texture::{CompressedImageFormats, ImageType},
+    tasks::AsyncComputeTaskPool,
 };
-use iyes_progress::ProgressCounter;
+use iyes_progress::{Progress, ProgressCounter};
 
-use super::clear_scene;
-use crate::{effects, game::State};
+use super::{clear_scene, RandomBoard};
+use crate::{
+    board::{self, Board, BoardError, GeneratorSettings},
+    effects,
+    game::{Announcement, SafeArea, State},
+    rng::GameRng,
+};
 
 pub struct Loading;
 
 impl Plugin for Loading {
     fn build(&self, app: &mut App) {
         app.add_system_set(SystemSet::on_enter(State::Loading).with_system(setup))
+            .add_system_set(SystemSet::on_enter(State::Loading).with_system(start_board_generator))
             .add_system_set(SystemSet::on_update(State::Loading).with_system(print_progress))
+            // the next random board is generated while a match is played, so it runs always
+            .add_system(track_board_generator.before(print_progress))
             .add_system_set(
                 SystemSet::on_exit(State::Loading).with_system(clear_scene::<OnLoadingScene>),
-            )
-            .add_system_set(
-                SystemSet::on_update(State::Loading)
-                    .with_system(fake_long_task.before(print_progress)),
             );
     }
 }
@@ -50,7 +60,13 @@ impl Plugin for Loading {
 struct OnLoadingScene;
 
 use bevy::asset::Assets as BevyAssets;
-fn setup(mut commands: Commands, mut images: ResMut<BevyAssets<Image>>) {
+fn setup(
+    mut commands: Commands,
+    mut images: ResMut<BevyAssets<Image>>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    ev_announcement.send(Announcement::scene("Loading"));
+
     let buff = include_bytes!("../../embedded/loading/load.png");
     let image_type = ImageType::Extension("png");
     let image = Image::from_buffer(buff, image_type, CompressedImageFormats::NONE, false)
@@ -67,6 +83,7 @@ fn setup(mut commands: Commands, mut images: ResMut<BevyAssets<Image>>) {
                 ..Default::default()
             },
             OnLoadingScene,
+            SafeArea,
         ))
         .with_children(|parent| {
             parent.spawn((
@@ -97,11 +114,102 @@ fn print_progress(progress: Option<Res<ProgressCounter>>, mut last_done: Local<u
     }
 }
 
-const DURATION_LONG_TASK_IN_SECS: f64 = 5.0;
-fn fake_long_task(time: Res<Time>, progress: Res<ProgressCounter>) {
-    if time.elapsed_seconds_f64() > DURATION_LONG_TASK_IN_SECS {
-        progress.manually_track(true.into());
-    } else {
-        progress.manually_track(false.into());
+#[derive(Default)]
+struct GeneratorShared {
+    done: AtomicU32,
+    total: AtomicU32,
+    result: Mutex<Option<Result<Board, BoardError>>>,
+}
+
+#[derive(Resource)]
+pub struct BoardGenerator {
+    shared: Arc<GeneratorShared>,
+    finished: bool,
+}
+
+impl BoardGenerator {
+    pub fn spawn(rng: &mut GameRng) -> Self {
+        let shared = Arc::new(GeneratorShared::default());
+        let task_shared = shared.clone();
+        let mut board_rng = rng.fork();
+        AsyncComputeTaskPool::get()
+            .spawn(async move {
+                let seed = board_rng.seed();
+                let result = board::generate(
+                    &GeneratorSettings::default(),
+                    &mut board_rng,
+                    &mut |done, total| {
+                        task_shared.total.store(total, Ordering::Relaxed);
+                        task_shared.done.store(done, Ordering::Relaxed);
+                    },
+                )
+                .map(|board| Board {
+                    name: format!("Random #{}", seed),
+                    ..board
+                });
+                *task_shared
+                    .result
+                    .lock()
+                    .expect("board generator result lock poisoned") = Some(result);
+            })
+            .detach();
+
+        Self {
+            shared,
+            finished: false,
+        }
+    }
+}
+
+fn start_board_generator(mut commands: Commands, mut rng: ResMut<GameRng>) {
+    commands.insert_resource(BoardGenerator::spawn(&mut rng));
+}
+
+fn track_board_generator(
+    mut commands: Commands,
+    generator: Option<ResMut<BoardGenerator>>,
+    progress: Option<Res<ProgressCounter>>,
+) {
+    let Some(mut generator) = generator else {
+        if let Some(progress) = progress {
+            progress.manually_track(false.into());
+        }
+        return;
+    };
+
+    if !generator.finished {
+        let result = generator
+            .shared
+            .result
+            .lock()
+            .expect("board generator result lock poisoned")
+            .take();
+        match result {
+            Some(Ok(board)) => {
+                debug!("generated board {:?}", board.name);
+                commands.insert_resource(RandomBoard(board));
+                generator.finished = true;
+            }
+            Some(Err(err)) => {
+                error!("can't generate a random board: {}", err);
+                generator.finished = true;
+            }
+            None => {}
+        }
+    }
+
+    match progress {
+        Some(progress) => {
+            let total = generator.shared.total.load(Ordering::Relaxed).max(1);
+            // we are not done until the board is out of the task
+            let done = if generator.finished {
+                total
+            } else {
+                generator.shared.done.load(Ordering::Relaxed).min(total - 1)
+            };
+            progress.manually_track(Progress { done, total });
+        }
+        None if generator.finished => commands.remove_resource::<BoardGenerator>(),
+        None => {}
     }
 }
@@ -21,55 +21,159 @@ OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 ***/
 
-use crate::game::{events, Assets, State};
-use crate::scenes::menu::Submenu;
+use super::{
+    super::board::{Playback, SavedMatch},
+    MenuStack,
+};
+use crate::{
+    game::{
+        events, last_replay, AnnouncerMode, Assets, BoardChoice, Config, Controller, DisplayMode,
+        LayoutMode, Palette, State, TextScale, ThemeKind,
+    },
+    rules::{Replay, Victory},
+};
 use bevy::{app::AppExit, prelude::*};
+use bevy_pkv::PkvStore;
+use serde::Deserialize;
 
-#[derive(Component, PartialEq)]
+#[derive(Component, Debug, Clone, PartialEq, Deserialize)]
 pub enum Action {
     Play,
-    Options,
-    Windowed,
-    FullScreen,
+    Continue,
+    WatchReplay,
+    Open(String),
+    DisplayMode(DisplayMode),
+    Layout(LayoutMode),
+    Theme(ThemeKind),
+    Board(BoardChoice),
+    TextScale(TextScale),
+    Palette(Palette),
+    ReducedMotion(bool),
+    Announcer(AnnouncerMode),
+    Victory(Victory),
+    GoldTarget(u32),
+    TurnLimit(u32),
+    Lives(u32),
+    Seat(usize, Controller),
+    Odds(bool),
     Quit,
     Back,
 }
 
+impl Action {
+    pub fn selected(&self, config: &Config) -> Option<bool> {
+        match self {
+            Action::DisplayMode(mode) => Some(config.mode == *mode),
+            Action::Layout(layout) => Some(config.layout == *layout),
+            Action::Theme(theme) => Some(config.theme == *theme),
+            Action::Board(board) => Some(config.board == *board),
+            Action::TextScale(text_scale) => Some(config.text_scale == *text_scale),
+            Action::Palette(palette) => Some(config.palette == *palette),
+            Action::ReducedMotion(reduced) => Some(config.reduced_motion == *reduced),
+            Action::Announcer(announcer) => Some(config.announcer == *announcer),
+            Action::Victory(victory) => Some(config.rules.victory == *victory),
+            Action::GoldTarget(gold) => Some(config.rules.gold_target == *gold),
+            Action::TurnLimit(turns) => Some(config.rules.turn_limit == *turns),
+            Action::Lives(lives) => Some(config.rules.lives == *lives),
+            Action::Seat(seat, controller) => Some(config.seats.get(*seat) == Some(controller)),
+            Action::Odds(show) => Some(config.show_odds == *show),
+            _ => None,
+        }
+    }
+}
+
 use bevy::prelude::State as BevyState;
 
+#[allow(clippy::too_many_arguments)]
 pub fn system(
+    mut commands: Commands,
     interaction_query: Query<(&Interaction, &Action), (Changed<Interaction>, With<Button>)>,
     mut app_exit_events: EventWriter<AppExit>,
     mut game_state: ResMut<BevyState<State>>,
+    store: Res<PkvStore>,
     assets: Res<Assets>,
     audio: Res<Audio>,
-    mut menu_state: ResMut<BevyState<Submenu>>,
+    mut menu_stack: ResMut<MenuStack>,
     mut ev_change_display_mode: EventWriter<events::ChangeDisplayMode>,
+    mut ev_change_layout: EventWriter<events::ChangeLayout>,
+    mut ev_change_theme: EventWriter<events::ChangeTheme>,
+    mut ev_change_accessibility: EventWriter<events::ChangeAccessibility>,
+    mut ev_change_board: EventWriter<events::ChangeBoard>,
+    mut ev_change_match_rules: EventWriter<events::ChangeMatchRules>,
+    mut ev_change_seat: EventWriter<events::ChangeSeat>,
+    mut ev_change_odds: EventWriter<events::ChangeOdds>,
 ) {
     for (interaction, button_action) in &interaction_query {
         if *interaction == Interaction::Clicked {
             match button_action {
                 Action::Quit => app_exit_events.send(AppExit),
-                Action::Play => {
-                    game_state
-                        .set(State::Hello)
-                        .expect("Failed to set game state");
-                    menu_state
-                        .set(Submenu::None)
-                        .expect("Failed to set menu state");
+                Action::Play => game_state
+                    .set(State::Board)
+                    .expect("Failed to set game state"),
+                Action::Continue => match SavedMatch::load(&store) {
+                    Some(saved) => {
+                        commands.insert_resource(saved);
+                        game_state
+                            .set(State::Board)
+                            .expect("Failed to set game state");
+                    }
+                    None => warn!("there is no match to continue"),
+                },
+                Action::WatchReplay => match Replay::load(last_replay()) {
+                    Ok(replay) => {
+                        commands.insert_resource(Playback::new(replay));
+                        game_state
+                            .set(State::Board)
+                            .expect("Failed to set game state");
+                    }
+                    Err(err) => warn!("can't watch the last match: {}", err),
+                },
+                Action::Open(menu) => menu_stack.open(menu),
+                Action::Back => menu_stack.back(),
+                Action::DisplayMode(mode) => {
+                    ev_change_display_mode.send(events::ChangeDisplayMode::to(*mode));
                 }
-                Action::Options => menu_state
-                    .set(Submenu::Options)
-                    .expect("Failed to set menu state"),
-                Action::Back => menu_state
-                    .set(Submenu::Main)
-                    .expect("Failed to set menu state"),
-                Action::Windowed => {
-                    ev_change_display_mode.send(events::ChangeDisplayMode::windowed());
+                Action::Layout(layout) => {
+                    ev_change_layout.send(events::ChangeLayout::to(*layout));
                 }
-
-                Action::FullScreen => {
-                    ev_change_display_mode.send(events::ChangeDisplayMode::full_screen());
+                Action::Theme(theme) => {
+                    ev_change_theme.send(events::ChangeTheme::to(*theme));
+                }
+                Action::Board(board) => {
+                    ev_change_board.send(events::ChangeBoard::to(*board));
+                }
+                Action::TextScale(text_scale) => {
+                    ev_change_accessibility
+                        .send(events::ChangeAccessibility::TextScale(*text_scale));
+                }
+                Action::Palette(palette) => {
+                    ev_change_accessibility.send(events::ChangeAccessibility::Palette(*palette));
+                }
+                Action::ReducedMotion(reduced) => {
+                    ev_change_accessibility
+                        .send(events::ChangeAccessibility::ReducedMotion(*reduced));
+                }
+                Action::Announcer(announcer) => {
+                    ev_change_accessibility
+                        .send(events::ChangeAccessibility::Announcer(*announcer));
+                }
+                Action::Victory(victory) => {
+                    ev_change_match_rules.send(events::ChangeMatchRules::Victory(*victory));
+                }
+                Action::GoldTarget(gold) => {
+                    ev_change_match_rules.send(events::ChangeMatchRules::GoldTarget(*gold));
+                }
+                Action::TurnLimit(turns) => {
+                    ev_change_match_rules.send(events::ChangeMatchRules::TurnLimit(*turns));
+                }
+                Action::Lives(lives) => {
+                    ev_change_match_rules.send(events::ChangeMatchRules::Lives(*lives));
+                }
+                Action::Seat(seat, controller) => {
+                    ev_change_seat.send(events::ChangeSeat::to(*seat, *controller));
+                }
+                Action::Odds(show) => {
+                    ev_change_odds.send(events::ChangeOdds::to(*show));
                 }
             }
             audio.play(assets.menu_click.clone());
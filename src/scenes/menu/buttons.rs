@@ -22,62 +22,74 @@ WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 ***/
 
 use super::actions::Action;
-use crate::game::Assets;
+use crate::game::{Announcement, Assets, Theme};
 use bevy::prelude::*;
 
 #[derive(Component)]
 pub struct SelectedButton;
 
-pub const NORMAL_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
-pub const HOVERED_COLOR: Color = Color::rgb(0.25, 0.25, 0.25);
-pub const HOVERED_SELECTED_COLOR: Color = Color::rgb(0.25, 0.65, 0.25);
-pub const CLICKED_COLOR: Color = Color::rgb(0.35, 0.75, 0.35);
-
-pub const TEXT_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
-
-const BUTTON_FONT_SIZE: f32 = 40.0;
-const BUTTON_FONT_SIZE_SMALL: f32 = 30.0;
-
 pub fn colors(
     mut interaction_query: Query<
         (&Interaction, &mut BackgroundColor, Option<&SelectedButton>),
         (Changed<Interaction>, With<Button>),
     >,
+    theme: Res<Theme>,
 ) {
     for (interaction, mut color, selected) in &mut interaction_query {
         *color = match (*interaction, selected) {
-            (Interaction::Clicked, _) => CLICKED_COLOR.into(),
-            (Interaction::Hovered, Some(_)) => HOVERED_SELECTED_COLOR.into(),
-            (Interaction::Hovered, None) => HOVERED_COLOR.into(),
-            (Interaction::None, Some(_)) => CLICKED_COLOR.into(),
-            (Interaction::None, None) => NORMAL_COLOR.into(),
+            (Interaction::Clicked, _) => theme.clicked_color.into(),
+            (Interaction::Hovered, Some(_)) => theme.hovered_selected_color.into(),
+            (Interaction::Hovered, None) => theme.hovered_color.into(),
+            (Interaction::None, Some(_)) => theme.clicked_color.into(),
+            (Interaction::None, None) => theme.normal_color.into(),
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn announce_focus(
+    interaction_query: Query<
+        (&Interaction, &Name, Option<&SelectedButton>),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    for (interaction, name, selected) in &interaction_query {
+        if *interaction == Interaction::Hovered {
+            let text = match selected {
+                Some(_) => format!("{}, selected", name),
+                None => format!("{}, button", name),
+            };
+            ev_announcement.send(Announcement::focus(text));
         }
     }
 }
 
-pub fn add(parent: &mut ChildBuilder, text: &str, action: Action, assets: &Assets) {
+pub fn add(parent: &mut ChildBuilder, text: &str, action: Action, assets: &Assets, theme: &Theme) {
     parent
         .spawn((
             ButtonBundle {
                 style: Style {
-                    size: Size::new(Val::Px(200.0), Val::Px(65.0)),
+                    min_size: Size::new(Val::Px(200.0), Val::Px(65.0)),
                     margin: UiRect::all(Val::Px(10.0)),
+                    padding: UiRect::horizontal(Val::Px(10.0)),
                     justify_content: JustifyContent::Center,
                     align_items: AlignItems::Center,
                     ..default()
                 },
-                background_color: NORMAL_COLOR.into(),
+                background_color: theme.normal_color.into(),
                 ..default()
             },
             action,
+            Name::new(text.to_string()),
         ))
         .with_children(|parent| {
             parent.spawn(TextBundle::from_section(
                 text,
                 TextStyle {
                     font: assets.default_font.clone(),
-                    font_size: BUTTON_FONT_SIZE,
-                    color: TEXT_COLOR,
+                    font_size: theme.button_font_size,
+                    color: theme.text_color,
                 },
             ));
         });
@@ -89,18 +101,20 @@ pub fn setting(
     selected: bool,
     action: Action,
     assets: &Assets,
+    theme: &Theme,
 ) {
     let color = if selected {
-        CLICKED_COLOR
+        theme.clicked_color
     } else {
-        NORMAL_COLOR
+        theme.normal_color
     };
 
     let mut button = parent.spawn((
         ButtonBundle {
             style: Style {
-                size: Size::new(Val::Px(150.0), Val::Px(50.0)),
+                min_size: Size::new(Val::Px(150.0), Val::Px(50.0)),
                 margin: UiRect::all(Val::Px(5.0)),
+                padding: UiRect::horizontal(Val::Px(10.0)),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
                 ..default()
@@ -109,6 +123,7 @@ pub fn setting(
             ..default()
         },
         action,
+        Name::new(text.to_string()),
     ));
 
     if selected {
@@ -120,20 +135,30 @@ pub fn setting(
             text,
             TextStyle {
                 font: assets.default_font.clone(),
-                font_size: BUTTON_FONT_SIZE_SMALL,
-                color: TEXT_COLOR,
+                font_size: theme.button_font_size_small,
+                color: theme.text_color,
             },
         ));
     });
 }
 
-fn select(entity: Entity, background_color: &mut Mut<BackgroundColor>, commands: &mut Commands) {
-    background_color.0 = CLICKED_COLOR;
+fn select(
+    entity: Entity,
+    background_color: &mut Mut<BackgroundColor>,
+    commands: &mut Commands,
+    theme: &Theme,
+) {
+    background_color.0 = theme.clicked_color;
     commands.entity(entity).insert(SelectedButton);
 }
 
-fn unselect(entity: Entity, background_color: &mut Mut<BackgroundColor>, commands: &mut Commands) {
-    background_color.0 = NORMAL_COLOR;
+fn unselect(
+    entity: Entity,
+    background_color: &mut Mut<BackgroundColor>,
+    commands: &mut Commands,
+    theme: &Theme,
+) {
+    background_color.0 = theme.normal_color;
     commands.entity(entity).remove::<SelectedButton>();
 }
 
@@ -142,10 +167,11 @@ pub fn change_selection(
     entity: Entity,
     background_color: &mut Mut<BackgroundColor>,
     commands: &mut Commands,
+    theme: &Theme,
 ) {
     if selected {
-        select(entity, background_color, commands);
+        select(entity, background_color, commands, theme);
     } else {
-        unselect(entity, background_color, commands);
+        unselect(entity, background_color, commands, theme);
     }
 }
@@ -0,0 +1,171 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use std::collections::HashMap;
+
+use bevy::{prelude::*, reflect::TypeUuid};
+use bevy_pkv::PkvStore;
+use serde::Deserialize;
+
+use super::{super::board::SavedMatch, actions::Action};
+use crate::game::last_replay;
+
+#[derive(Debug, Deserialize, TypeUuid)]
+#[uuid = "5d0c8a0e-3f0b-4c57-9a47-1f1b6d3c2e71"]
+pub struct Menus {
+    pub root: String,
+    pub menus: HashMap<String, MenuDefinition>,
+}
+
+impl Menus {
+    pub fn get(&self, name: &str) -> Option<&MenuDefinition> {
+        self.menus.get(name)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MenuDefinition {
+    pub title: String,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum Entry {
+    Button {
+        text: String,
+        action: Action,
+        #[serde(default)]
+        condition: Option<Condition>,
+    },
+    Setting {
+        label: String,
+        options: Vec<SettingOption>,
+        #[serde(default)]
+        condition: Option<Condition>,
+    },
+}
+
+impl Entry {
+    pub fn visible(&self, store: &PkvStore) -> bool {
+        let condition = match self {
+            Entry::Button { condition, .. } => condition,
+            Entry::Setting { condition, .. } => condition,
+        };
+        condition.is_none_or(|condition| condition.met(store))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SettingOption {
+    pub text: String,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+pub enum Condition {
+    SaveExists,
+    NoSave,
+    ReplayExists,
+}
+
+impl Condition {
+    pub fn met(&self, store: &PkvStore) -> bool {
+        let save_exists = SavedMatch::load(store).is_some();
+        match self {
+            Condition::SaveExists => save_exists,
+            Condition::NoSave => !save_exists,
+            Condition::ReplayExists => last_replay().exists(),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default, Clone)]
+pub struct MenuStack {
+    stack: Vec<String>,
+}
+
+impl MenuStack {
+    pub fn open(&mut self, name: &str) {
+        self.stack.push(name.into());
+    }
+
+    pub fn back(&mut self) {
+        if self.stack.len() > 1 {
+            self.stack.pop();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.stack.last().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn actions(entry: &Entry) -> Vec<&Action> {
+        match entry {
+            Entry::Button { action, .. } => vec![action],
+            Entry::Setting { options, .. } => options.iter().map(|option| &option.action).collect(),
+        }
+    }
+
+    #[test]
+    fn menu_files_parse() {
+        let folder = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/menu");
+        let mut parsed = 0;
+        for file in fs::read_dir(folder).unwrap() {
+            let path = file.unwrap().path();
+            if !path.to_string_lossy().ends_with(".menu.ron") {
+                continue;
+            }
+            let text = fs::read_to_string(&path).unwrap();
+            let menus: Menus = ron::from_str(&text)
+                .unwrap_or_else(|err| panic!("can't parse {}: {}", path.display(), err));
+            assert!(menus.get(&menus.root).is_some(), "{}", path.display());
+            // every menu opened from another one has to be there
+            for (name, definition) in &menus.menus {
+                for action in definition.entries.iter().flat_map(actions) {
+                    if let Action::Open(menu) = action {
+                        assert!(
+                            menus.get(menu).is_some(),
+                            "{} opens missing menu {:?} from {:?}",
+                            path.display(),
+                            menu,
+                            name
+                        );
+                    }
+                }
+            }
+            parsed += 1;
+        }
+        assert!(parsed > 0, "no menu files in {}", folder);
+    }
+}
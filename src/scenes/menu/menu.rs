@@ -25,53 +25,97 @@ use super::{
     super::clear_scene,
     actions::{self, Action},
     buttons,
+    definition::{Entry, MenuDefinition, MenuStack, Menus},
 };
-use crate::game::{events, Assets, Config, DisplayMode, State};
+use crate::game::{Announcement, Assets, Config, SafeArea, State, Theme};
 use bevy::prelude::*;
-
-#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
-pub enum Submenu {
-    None,
-    Main,
-    Options,
-}
+use bevy_common_assets::ron::RonAssetPlugin;
+use bevy_pkv::PkvStore;
 
 pub struct Menu;
 
 impl Plugin for Menu {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_enter(State::Menu).with_system(setup))
+        app.add_plugin(RonAssetPlugin::<Menus>::new(&["menu.ron"]))
+            .init_resource::<MenuStack>()
+            .add_system_set(SystemSet::on_enter(State::Menu).with_system(setup))
             .add_system_set(SystemSet::on_update(State::Menu).with_system(buttons::colors))
+            .add_system_set(SystemSet::on_update(State::Menu).with_system(buttons::announce_focus))
             .add_system_set(SystemSet::on_update(State::Menu).with_system(actions::system))
-            .add_system_set(SystemSet::on_exit(State::Menu).with_system(clear_scene::<OnMenuScene>))
-            .add_state(Submenu::None)
-            .add_system_set(SystemSet::on_enter(Submenu::Main).with_system(setup_main))
-            .add_system_set(
-                SystemSet::on_exit(Submenu::Main).with_system(clear_scene::<OnMenuScene>),
-            )
-            .add_system_set(SystemSet::on_enter(Submenu::Options).with_system(setup_options))
             .add_system_set(
-                SystemSet::on_update(Submenu::Options).with_system(update_options_buttons),
+                SystemSet::on_update(State::Menu).with_system(show_menu.after(actions::system)),
             )
+            .add_system_set(SystemSet::on_update(State::Menu).with_system(update_settings))
             .add_system_set(
-                SystemSet::on_exit(Submenu::Options).with_system(clear_scene::<OnMenuScene>),
+                SystemSet::on_exit(State::Menu)
+                    .with_system(clear_scene::<OnMenuScene>)
+                    .with_system(exit),
             );
     }
 }
 
-const FONT_SIZE: f32 = 80.0;
-const FONT_SIZE_SMALL: f32 = 45.0;
-const FONT_COLOR: Color = Color::WHITE;
-
 #[derive(Component)]
 struct OnMenuScene;
 
-use bevy::prelude::State as BevyState;
-fn setup(mut menu_state: ResMut<BevyState<Submenu>>) {
-    let _ = menu_state.set(Submenu::Main);
+use bevy::asset::Assets as BevyAssets;
+fn setup(mut menu_stack: ResMut<MenuStack>, assets: Res<Assets>, menus: Res<BevyAssets<Menus>>) {
+    let menus = menus
+        .get(&assets.menus)
+        .expect("menus should be loaded before the menu scene");
+    // a scene can leave a menu open for when it comes back here
+    if menu_stack.current().is_none() {
+        menu_stack.open(&menus.root);
+    }
+}
+
+fn exit(mut menu_stack: ResMut<MenuStack>) {
+    menu_stack.clear();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn show_menu(
+    mut commands: Commands,
+    menu_stack: Res<MenuStack>,
+    assets: Res<Assets>,
+    menus: Res<BevyAssets<Menus>>,
+    config: Res<Config>,
+    store: Res<PkvStore>,
+    theme: Res<Theme>,
+    to_clear: Query<Entity, With<OnMenuScene>>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    if !menu_stack.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    for entity in &to_clear {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some(name) = menu_stack.current() else {
+        return;
+    };
+    let menus = menus
+        .get(&assets.menus)
+        .expect("menus should be loaded before the menu scene");
+    if let Some(definition) = menus.get(name) {
+        spawn_menu(&mut commands, definition, &assets, &config, &store, &theme);
+        if menu_stack.is_changed() {
+            ev_announcement.send(Announcement::scene(format!("{} menu", definition.title)));
+        }
+    } else {
+        error!("menu {:?} is not defined", name);
+    }
 }
 
-fn setup_main(mut commands: Commands, assets: Res<Assets>) {
+fn spawn_menu(
+    commands: &mut Commands,
+    definition: &MenuDefinition,
+    assets: &Assets,
+    config: &Config,
+    store: &PkvStore,
+    theme: &Theme,
+) {
     commands
         .spawn((
             NodeBundle {
@@ -84,6 +128,7 @@ fn setup_main(mut commands: Commands, assets: Res<Assets>) {
                 ..default()
             },
             OnMenuScene,
+            SafeArea,
         ))
         .with_children(|parent| {
             parent
@@ -93,17 +138,17 @@ fn setup_main(mut commands: Commands, assets: Res<Assets>) {
                         align_items: AlignItems::Center,
                         ..default()
                     },
-                    background_color: Color::GRAY.into(),
+                    background_color: theme.panel_color.into(),
                     ..default()
                 })
                 .with_children(|parent| {
                     parent.spawn(
                         TextBundle::from_section(
-                            "Menu",
+                            definition.title.as_str(),
                             TextStyle {
                                 font: assets.default_font.clone(),
-                                font_size: FONT_SIZE,
-                                color: FONT_COLOR,
+                                font_size: theme.title_font_size,
+                                color: theme.title_color,
                             },
                         )
                         .with_style(Style {
@@ -111,114 +156,87 @@ fn setup_main(mut commands: Commands, assets: Res<Assets>) {
                             ..default()
                         }),
                     );
-                    buttons::add(parent, "Play", Action::Play, assets.as_ref());
-                    buttons::add(parent, "Options", Action::Options, assets.as_ref());
-                    buttons::add(parent, "Quit", Action::Quit, assets.as_ref());
+                    for entry in definition
+                        .entries
+                        .iter()
+                        .filter(|entry| entry.visible(store))
+                    {
+                        spawn_entry(parent, entry, assets, config, theme);
+                    }
                 });
         });
 }
 
-fn setup_options(mut commands: Commands, assets: Res<Assets>, config: Res<Config>) {
-    commands
-        .spawn((
-            NodeBundle {
-                style: Style {
-                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
-                    align_items: AlignItems::Center,
-                    justify_content: JustifyContent::Center,
-                    ..default()
-                },
-                ..default()
-            },
-            OnMenuScene,
-        ))
-        .with_children(|parent| {
+fn spawn_entry(
+    parent: &mut ChildBuilder,
+    entry: &Entry,
+    assets: &Assets,
+    config: &Config,
+    theme: &Theme,
+) {
+    match entry {
+        Entry::Button { text, action, .. } => {
+            buttons::add(parent, text, action.clone(), assets, theme);
+        }
+        Entry::Setting { label, options, .. } => {
             parent
                 .spawn(NodeBundle {
                     style: Style {
-                        flex_direction: FlexDirection::Column,
+                        flex_direction: FlexDirection::Row,
                         align_items: AlignItems::Center,
                         ..default()
                     },
-                    background_color: Color::GRAY.into(),
+                    background_color: theme.panel_color.into(),
                     ..default()
                 })
                 .with_children(|parent| {
                     parent.spawn(
                         TextBundle::from_section(
-                            "Options",
+                            label.as_str(),
                             TextStyle {
                                 font: assets.default_font.clone(),
-                                font_size: FONT_SIZE,
-                                color: FONT_COLOR,
+                                font_size: theme.label_font_size,
+                                color: theme.title_color,
                             },
                         )
                         .with_style(Style {
-                            margin: UiRect::all(Val::Px(50.0)),
+                            margin: UiRect::all(Val::Px(10.0)),
                             ..default()
                         }),
                     );
-                    parent
-                        .spawn(NodeBundle {
-                            style: Style {
-                                flex_direction: FlexDirection::Row,
-                                align_items: AlignItems::Center,
-                                ..default()
-                            },
-                            background_color: Color::GRAY.into(),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            parent.spawn(
-                                TextBundle::from_section(
-                                    "Display:",
-                                    TextStyle {
-                                        font: assets.default_font.clone(),
-                                        font_size: FONT_SIZE_SMALL,
-                                        color: FONT_COLOR,
-                                    },
-                                )
-                                .with_style(Style {
-                                    margin: UiRect::all(Val::Px(10.0)),
-                                    ..default()
-                                }),
-                            );
-                            buttons::setting(
-                                parent,
-                                "Windowed",
-                                config.mode == DisplayMode::Windowed,
-                                Action::Windowed,
-                                assets.as_ref(),
-                            );
-                            buttons::setting(
-                                parent,
-                                "Full Screen",
-                                config.mode == DisplayMode::FullScreen,
-                                Action::FullScreen,
-                                assets.as_ref(),
-                            );
-                        });
-                    buttons::add(parent, "Back", Action::Back, assets.as_ref());
+                    for option in options {
+                        buttons::setting(
+                            parent,
+                            &option.text,
+                            option.action.selected(config).unwrap_or(false),
+                            option.action.clone(),
+                            assets,
+                            theme,
+                        );
+                    }
                 });
-        });
+        }
+    }
 }
 
-fn update_options_buttons(
-    mut ev_change_display_mode: EventReader<events::ChangeDisplayMode>,
+fn update_settings(
+    config: Res<Config>,
+    theme: Res<Theme>,
     mut buttons_query: Query<(Entity, &mut BackgroundColor, &Action)>,
     mut commands: Commands,
 ) {
-    for change_display_mode in ev_change_display_mode.iter() {
-        for (entity, mut background_color, action) in buttons_query.iter_mut() {
-            let need_selection_change = match action {
-                Action::Windowed => Some(DisplayMode::Windowed),
-                Action::FullScreen => Some(DisplayMode::FullScreen),
-                _ => None,
-            };
-            if let Some(mode) = need_selection_change {
-                let selected = mode == change_display_mode.mode;
-                buttons::change_selection(selected, entity, &mut background_color, &mut commands);
-            }
+    if !config.is_changed() {
+        return;
+    }
+    for (entity, mut background_color, action) in buttons_query.iter_mut() {
+        if let Some(selected) = action.selected(&config) {
+            buttons::change_selection(
+                selected,
+                entity,
+                &mut background_color,
+                &mut commands,
+                &theme,
+            );
         }
     }
 }
@@ -23,7 +23,9 @@ WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
 mod menu;
 pub use menu::Menu;
-pub use menu::Submenu;
+
+mod definition;
+pub use definition::{MenuStack, Menus};
 
 mod actions;
 mod buttons;
@@ -23,18 +23,35 @@ WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
 use bevy::prelude::*;
 
-mod hello;
-pub use hello::Hello;
+mod board;
+pub use board::{BoardMap, BoardScene, RandomBoard};
+
+mod battle;
+pub use battle::BattleScene;
+
+mod results;
+pub use results::Results;
 
 mod splash;
 pub use splash::Splash;
 
 mod menu;
-pub use menu::Menu;
+pub use menu::{Menu, Menus};
 
 mod loading;
 pub use loading::Loading;
 
+fn confirm_pressed(
+    keyboard: &Input<KeyCode>,
+    gamepads: &Gamepads,
+    buttons: &Input<GamepadButton>,
+) -> bool {
+    keyboard.any_just_pressed([KeyCode::Space, KeyCode::Return])
+        || gamepads.iter().any(|gamepad| {
+            buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::South))
+        })
+}
+
 fn clear_scene<T: Component>(to_clear: Query<Entity, With<T>>, mut commands: Commands) {
     for entity in &to_clear {
         commands.entity(entity).despawn_recursive();
@@ -0,0 +1,258 @@
+/***
+Copyright (c) 2022 Juan Medina
+
+Permission is hereby granted, free of charge, to any person obtaining
+a copy of this software and associated documentation files (the
+"Software"), to deal in the Software without restriction, including
+without limitation the rights to use, copy, modify, merge, publish,
+distribute, sublicense, and/or sell copies of the Software, and to
+permit persons to whom the Software is furnished to do so, subject to
+the following conditions:
+
+The above copyright notice and this permission notice shall be
+included in all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+***/
+
+use bevy::prelude::*;
+
+use super::{
+    clear_scene, confirm_pressed,
+    menu::{MenuStack, Menus},
+};
+use crate::{
+    game::{Announcement, Assets, SafeArea, State, Theme},
+    rules::{Standing, Victory},
+};
+
+const SETUP_MENU: &str = "setup";
+const NAME_WIDTH: f32 = 220.0;
+const COLUMN_WIDTH: f32 = 130.0;
+
+pub struct Results;
+
+impl Plugin for Results {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(State::Results).with_system(setup))
+            .add_system_set(SystemSet::on_update(State::Results).with_system(results_input))
+            .add_system_set(
+                SystemSet::on_exit(State::Results)
+                    .with_system(clear_scene::<OnResultsScene>)
+                    .with_system(exit),
+            );
+    }
+}
+
+#[derive(Component)]
+struct OnResultsScene;
+
+// how the match ended, left by the board before it clears the match
+#[derive(Resource)]
+pub struct MatchResults {
+    pub victory: Victory,
+    pub winner: String,
+    pub turn: u32,
+    pub standings: Vec<Standing>,
+}
+
+fn setup(
+    mut commands: Commands,
+    results: Option<Res<MatchResults>>,
+    assets: Res<Assets>,
+    theme: Res<Theme>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    let Some(results) = results else {
+        return;
+    };
+    ev_announcement.send(Announcement::scene(format!(
+        "Results, {} wins, {}",
+        results.winner,
+        results.victory.name()
+    )));
+    for (rank, standing) in results.standings.iter().enumerate() {
+        ev_announcement.send(Announcement::game(format!(
+            "{}. {}, {} {}",
+            rank + 1,
+            standing.name,
+            results.victory.score_name().to_lowercase(),
+            standing.score
+        )));
+    }
+
+    let text = |value: String, font_size: f32, color: Color| {
+        TextBundle::from_section(
+            value,
+            TextStyle {
+                font: assets.default_font.clone(),
+                font_size,
+                color,
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(6.0)),
+            ..default()
+        })
+    };
+    let style = |color: Color| TextStyle {
+        font: assets.default_font.clone(),
+        font_size: theme.button_font_size_small,
+        color,
+    };
+
+    let headers = [
+        results.victory.score_name(),
+        "Gold",
+        "Battles",
+        "Monsters",
+        "Defeats",
+        "Steps",
+    ];
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            SafeArea,
+            OnResultsScene,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..default()
+                    },
+                    background_color: theme.panel_color.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(text(
+                        format!("{} wins!", results.winner),
+                        theme.title_font_size,
+                        theme.title_color,
+                    ));
+                    parent.spawn(text(
+                        format!("{}, round {}", results.victory.name(), results.turn),
+                        theme.label_font_size,
+                        theme.text_color,
+                    ));
+                    spawn_row(
+                        parent,
+                        String::new(),
+                        headers.iter().map(|header| header.to_string()).collect(),
+                        style(theme.title_color),
+                    );
+                    for (rank, standing) in results.standings.iter().enumerate() {
+                        let name = if standing.out {
+                            format!("{}. {} (out)", rank + 1, standing.name)
+                        } else {
+                            format!("{}. {}", rank + 1, standing.name)
+                        };
+                        let record = standing.record;
+                        let values = [
+                            standing.score,
+                            standing.gold,
+                            record.battles_won,
+                            record.monsters_defeated,
+                            record.defeats,
+                            record.steps,
+                        ];
+                        spawn_row(
+                            parent,
+                            name,
+                            values.iter().map(|value| value.to_string()).collect(),
+                            style(theme.text_color),
+                        );
+                    }
+                    parent.spawn(text(
+                        "Press Space to play again, Backspace for the menu".into(),
+                        theme.button_font_size_small,
+                        theme.text_color,
+                    ));
+                });
+        });
+}
+
+// a line of the standings with the name column wider than the rest
+fn spawn_row(parent: &mut ChildBuilder, name: String, values: Vec<String>, style: TextStyle) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            let widths = std::iter::once(NAME_WIDTH).chain(std::iter::repeat(COLUMN_WIDTH));
+            for (value, width) in std::iter::once(name).chain(values).zip(widths) {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(width), Val::Auto),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(value, style.clone()).with_style(
+                            Style {
+                                margin: UiRect::all(Val::Px(6.0)),
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn exit(mut commands: Commands) {
+    commands.remove_resource::<MatchResults>();
+}
+
+use bevy::asset::Assets as BevyAssets;
+use bevy::prelude::State as BevyState;
+fn results_input(
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    assets: Res<Assets>,
+    menus: Res<BevyAssets<Menus>>,
+    mut menu_stack: ResMut<MenuStack>,
+    mut game_state: ResMut<BevyState<State>>,
+) {
+    let back = keyboard.just_pressed(KeyCode::Back)
+        || gamepads.iter().any(|gamepad| {
+            buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::East))
+        });
+    if confirm_pressed(&keyboard, &gamepads, &buttons) {
+        // play again goes back to the match setup with the same rules picked
+        let menus = menus
+            .get(&assets.menus)
+            .expect("menus should be loaded before the results scene");
+        menu_stack.clear();
+        menu_stack.open(&menus.root);
+        menu_stack.open(SETUP_MENU);
+    } else if !back {
+        return;
+    }
+    game_state
+        .set(State::Menu)
+        .expect("Failed to set game state");
+}
@@ -24,7 +24,7 @@ WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 use super::clear_scene;
 use crate::{
     effects::{fade, Go},
-    game::{Assets, State},
+    game::{Announcement, Assets, Config, State},
 };
 use bevy::prelude::*;
 
@@ -47,18 +47,35 @@ const PAUSE: u64 = 2;
 const OUT: u64 = 1;
 const DELAY: f32 = (IN + PAUSE + OUT + 1) as f32;
 
-fn setup(mut commands: Commands, audio: Res<Audio>, assets: Res<Assets>) {
-    commands.spawn((
-        SpriteBundle {
-            sprite: fade::out_sprite(),
-            texture: assets.newolds_logo.clone(),
-            ..default()
-        },
-        OnSplashScene,
-        fade::in_out_sprite(IN, PAUSE, OUT),
-    ));
+fn setup(
+    mut commands: Commands,
+    audio: Res<Audio>,
+    assets: Res<Assets>,
+    config: Res<Config>,
+    mut ev_announcement: EventWriter<Announcement>,
+) {
+    if config.reduced_motion {
+        commands.spawn((
+            SpriteBundle {
+                texture: assets.newolds_logo.clone(),
+                ..default()
+            },
+            OnSplashScene,
+        ));
+    } else {
+        commands.spawn((
+            SpriteBundle {
+                sprite: fade::out_sprite(),
+                texture: assets.newolds_logo.clone(),
+                ..default()
+            },
+            OnSplashScene,
+            fade::in_out_sprite(IN, PAUSE, OUT),
+        ));
+    }
 
     audio.play(assets.newolds_sound.clone());
+    ev_announcement.send(Announcement::scene("NewOlds presents"));
 
     commands.insert_resource(Go::to(State::Menu).after(DELAY));
 }